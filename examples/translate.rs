@@ -0,0 +1,33 @@
+//! Translate a transcript with the genetic code of its sequence region, or one given on the command line.
+//!
+//! `cargo run --example translate -- [TRANSCRIPT_ID] [GENETIC_CODE]`
+//! * TRANSCRIPT_ID defaults to ENST00000361390, MT-ND1.
+//! * GENETIC_CODE is an NCBI table number or name, eg `1` or `vertebrate_mitochondrial`.
+use anyhow::{anyhow, Result};
+use rs_embl::{
+    sequence::GenomicSequence,
+    transcript::{translate_with, GeneticCode, Transcript},
+    Getter,
+};
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let id = args.next().unwrap_or_else(|| "ENST00000361390".to_owned());
+    let code = args
+        .next()
+        .map(|code| code.parse::<GeneticCode>())
+        .transpose()
+        .map_err(|err| anyhow!(err))?;
+    let transcript_getter = Getter::<Transcript>::new();
+    let sequence_getter = Getter::<GenomicSequence>::new();
+    let transcript = transcript_getter.client().get(id.clone()).await?;
+    let seq = sequence_getter.client().get(id).await?;
+    let code = code.unwrap_or_else(|| transcript.genetic_code());
+    let translation = translate_with(&transcript.coding_sequence(&seq), code);
+    println!(
+        ">{} {} chromosome:{} {:?}",
+        transcript.id, transcript.display_name, transcript.seq_region_name, code
+    );
+    println!("{}", translation.protein_sequence);
+    Ok(())
+}
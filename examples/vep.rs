@@ -1,55 +1,34 @@
 use anyhow::Result;
-use rs_embl::{sequence::GenomicSequence, transcript::Transcript, vep::VEPAnalysis, Getter};
+use rs_embl::{pipeline::annotate_variant, Getter};
 #[tokio::main]
 async fn main() -> Result<()> {
-    let vep_getter = Getter::<VEPAnalysis>::new();
-    let transcript_getter = Getter::<Transcript>::new();
-    let sequence_getter = Getter::<GenomicSequence>::new();
+    let vep_getter = Getter::new();
+    let transcript_getter = Getter::new();
+    let sequence_getter = Getter::new();
     let handles = ["18:g.31592974G>A"]
         .iter()
         .map(|id| {
             let v = vep_getter.client();
             let t = transcript_getter.client();
-            tokio::spawn(async move {
-                let Some(vep) = v.get((*id).to_owned()).await else {
-                    return None;
-                };
-                let consequences = vep.transcript_consequences.clone();
-                let handles = consequences
-                    .into_iter()
-                    .map(|transcript| {
-                        let client = t.clone();
-                        tokio::spawn(
-                            async move { client.get(transcript.transcript_id.clone()).await },
-                        )
-                    })
-                    .collect::<Vec<_>>();
-                Some((vep, handles))
-            })
+            let s = sequence_getter.client();
+            tokio::spawn(async move { annotate_variant(id, v, t, s).await })
         })
         .collect::<Vec<_>>();
     for v in handles.into_iter() {
-        let Some((vep, tcs)) = v.await.unwrap() else {
-            continue;
-        };
-        println!("{:#?}", vep);
-        for tc in tcs {
-            let Some(tc) = tc.await.unwrap() else {
+        let annotated = match v.await.unwrap() {
+            Ok(annotated) => annotated,
+            Err(err) => {
+                eprintln!("{err}");
                 continue;
-            };
-            println!("{:#?}", tc);
-            let genomic_seq = tc.genomic_sequence(sequence_getter.client()).await;
-            let exons = genomic_seq.exons();
-            println!("{:#?}", exons);
-            let exon1 = exons.into_iter().next();
-            let output = match exon1 {
-                Some(s) => {
-                    let Some(t) = tc.translation else { continue };
-                    &(s.get((t.start - tc.start) as usize..).unwrap_or(""))
-                }
-                None => "",
-            };
-            println!("{}", output);
+            }
+        };
+        println!("{:#?}", annotated.vep);
+        for transcript in annotated.transcripts {
+            println!("{:#?}", transcript.transcript);
+            println!("{:#?}", transcript.consequences);
+        }
+        for err in annotated.errors {
+            eprintln!("{err}");
         }
     }
 
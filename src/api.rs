@@ -1,25 +1,57 @@
+use itertools::Itertools;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use tokio::sync::mpsc;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::sync::{broadcast, watch};
 use tokio::time::{sleep, Duration};
 
 use tokio::spawn;
 
-/// The minimum time between post operations.
+use crate::logging::{log_debug, log_error, log_warn};
+
+/// How long a partial batch waits for more requests before it is posted, by default.
+/// * The window starts when the first request of the batch is queued, so no request waits longer
+///   than this for its batch to be posted.
+/// * Batches reaching [EnsemblPostEndpoint::max_post_size] are posted immediately.
+/// * Also the default spacing between posts, see [GetterBuilder::min_request_spacing].
 pub const WAIT_DELAY: Duration = Duration::from_millis(500);
-const ENSEMBL_SERVER: &str = r#"https://rest.ensembl.org"#;
+/// The error for requests missing from an otherwise successful response.
+const NO_RESULT: &str = "Ensembl did not return a result";
+/// How many times [Client::get] retries a request that failed with a transient status.
+pub const MAX_RETRIES: u32 = 3;
+/// How long a [Getter] pauses posting after a transient failure, doubling for each consecutive one.
+pub const RETRY_DELAY: Duration = Duration::from_secs(1);
+/// The longest a [Getter] pauses after consecutive transient failures.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(64);
+/// The default cap on the size of a posted payload, see [GetterBuilder::max_payload_bytes].
+pub const MAX_PAYLOAD_BYTES: usize = 1_000_000;
+pub(crate) const ENSEMBL_SERVER: &str = r#"https://rest.ensembl.org"#;
+pub(crate) const HEADERS: &[(&str, &str)] = &[
+    ("Content-Type", "application/json"),
+    ("Accept", "application/json"),
+];
+/// The channel a single request's result is sent back on.
+type Responder<T> = tokio::sync::oneshot::Sender<Result<T, EnsemblError>>;
+/// Checks an identifier before it is queued, see [EnsemblPostEndpoint::check_input].
+type InputCheck = fn(&str) -> Result<(), String>;
 /// Encapsulates Ensembl REST API calls to allow multiple entries to be condensed into a single POST request.
 ///  * This will spawn a new asyncronous task that will periodically poll for new requests and handle them.
-///  * The task will abort when the [Getter] object is dropped.
+///  * Cloning a [Getter] shares its task. The task aborts when the last clone is dropped, even if
+///    [Client]s remain: their outstanding requests fail and new requests panic.
 /// ```
 /// # tokio::runtime::Builder::new_current_thread()
 /// #       .enable_all()
 /// #       .build()
 /// #       .unwrap()
 /// #       .block_on(async {
-/// use rs_embl::{Getter, vep::VEPAnalysis};
+/// use rs_embl::{EnsemblError, Getter, vep::VEPAnalysis};
 ///
 /// // Wrap in an async tokio runtime
 ///
@@ -34,7 +66,7 @@ const ENSEMBL_SERVER: &str = r#"https://rest.ensembl.org"#;
 ///     })
 ///     .collect();
 /// for h in handles.into_iter() {
-///     let vep: Option<VEPAnalysis> = h.await.unwrap();
+///     let vep: Result<VEPAnalysis, EnsemblError> = h.await.unwrap();
 ///     println!("{:#?}", vep.unwrap());
 /// }
 /// # });
@@ -42,8 +74,59 @@ const ENSEMBL_SERVER: &str = r#"https://rest.ensembl.org"#;
 #[cfg(not(target_arch = "wasm32"))]
 #[derive(Debug)]
 pub struct Getter<T: EnsemblPostEndpoint + Send + DeserializeOwned> {
-    //is_alive: Arc<AtomicBool>,
-    tx: mpsc::Sender<(String, tokio::sync::oneshot::Sender<T>)>,
+    tx: mpsc::Sender<(String, Responder<T>)>,
+    check_input: InputCheck,
+    negative_cache: Option<Arc<NegativeCache>>,
+    rate_limits: broadcast::Sender<RateLimitEvent>,
+    paused: Arc<watch::Sender<bool>>,
+    post_size: Arc<AtomicUsize>,
+    guard: Arc<TaskGuard>,
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: EnsemblPostEndpoint + Send + DeserializeOwned> Clone for Getter<T> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            check_input: self.check_input,
+            negative_cache: self.negative_cache.clone(),
+            rate_limits: self.rate_limits.clone(),
+            paused: self.paused.clone(),
+            post_size: self.post_size.clone(),
+            guard: self.guard.clone(),
+        }
+    }
+}
+
+/// Emitted by a [Getter] when Ensembl rejects a post as rate limited (429) or unavailable (503).
+/// * Subscribe with [Getter::rate_limit_events].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RateLimitEvent {
+    pub status: u16,
+    /// How long the [Getter] pauses posting before the rejected requests are retried, see [RETRY_DELAY].
+    pub wait: Duration,
+}
+
+// The states of a Getter's polling task, shared with its Clients.
+/// The task is polling for requests.
+const TASK_ALIVE: u8 = 0;
+/// Every clone of the [Getter] was dropped.
+const TASK_DROPPED: u8 = 1;
+/// The task shut down after [GetterBuilder::idle_timeout].
+const TASK_IDLE: u8 = 2;
+
+/// Shared by the clones of a [Getter], aborting its polling task when the last one is dropped.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+struct TaskGuard {
+    task_state: Arc<AtomicU8>,
+    task: tokio::task::JoinHandle<()>,
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.task_state.store(TASK_DROPPED, Ordering::Release);
+        self.task.abort();
+    }
 }
 
 impl<T: 'static + EnsemblPostEndpoint + Send + DeserializeOwned> Default for Getter<T> {
@@ -55,151 +138,1094 @@ impl<T: 'static + EnsemblPostEndpoint + Send + DeserializeOwned> Default for Get
 impl<T: 'static + EnsemblPostEndpoint + Send + DeserializeOwned> Getter<T> {
     /// Create a new Getter object to return T from Enseble REST endpoint.
     pub fn new() -> Self {
-        let (tx, mut rx) = mpsc::channel::<(String, tokio::sync::oneshot::Sender<T>)>(500);
-        {
-            #[cfg(not(target_arch = "wasm32"))]
-            let client = reqwest::Client::new();
-            {
-                spawn(async move {
-                    loop {
-                        sleep(WAIT_DELAY).await;
-                        let mut gets = HashMap::new();
-                        let Some((key, value)) = rx.recv().await else {
-                            break;
-                        };
-                        gets.insert(key, value);
-                        while let Ok((k, v)) = rx.try_recv() {
-                            gets.insert(k, v);
-                        }
-                        #[cfg(not(target_arch = "wasm32"))]
-                        Self::process(gets, &client).await;
-                        #[cfg(target_arch = "wasm32")]
-                        Self::process(gets).await;
-                    }
-                    rx.close();
-                    let mut gets = HashMap::new();
-                    while let Some((k, v)) = rx.recv().await {
-                        gets.insert(k, v);
-                    }
-                    #[cfg(not(target_arch = "wasm32"))]
-                    Self::process(gets, &client).await;
-                    #[cfg(target_arch = "wasm32")]
-                    Self::process(gets).await;
-                });
-            }
-        }
-        Self { tx }
+        Self::builder().build()
+    }
+
+    /// Create a [GetterBuilder] to configure the Getter before spawning its task.
+    pub fn builder() -> GetterBuilder<T> {
+        GetterBuilder::new()
+    }
+
+    /// Subscribe to the [RateLimitEvent]s of this Getter and its clones, eg to pause producers.
+    /// * Only events emitted after subscribing are received. A receiver that falls behind skips
+    ///   the oldest events, see [broadcast::Receiver::recv].
+    /// * Results are still delivered to the requests as [EnsemblError]s.
+    pub fn rate_limit_events(&self) -> broadcast::Receiver<RateLimitEvent> {
+        self.rate_limits.subscribe()
+    }
+
+    /// Stop posting to Ensembl until [Getter::resume], eg for a maintenance window.
+    /// * Applies to this Getter and its clones. Requests keep queueing, and wait rather than fail.
+    /// * A post already sent completes, but no further chunk is posted, including retries.
+    pub fn pause(&self) {
+        self.paused.send_replace(true);
+    }
+
+    /// Resume posting after [Getter::pause], starting with the requests queued meanwhile.
+    pub fn resume(&self) {
+        self.paused.send_replace(false);
+    }
+
+    /// Whether the Getter is paused by [Getter::pause].
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// The most requests currently posted together, adapted to rate limiting between
+    /// [GetterBuilder::min_post_size] and [EnsemblPostEndpoint::max_post_size].
+    pub fn effective_post_size(&self) -> usize {
+        self.post_size.load(Ordering::Relaxed)
     }
 
+    /// Post the requests in `input` in chunks of at most the [Pacing::post_size] requests and
+    /// `max_payload_bytes`.
+    /// * Each post first waits on `pacing`, see [Pacing::ready].
+    /// * A transient failure pauses posting for the backoff, which then doubles up to
+    ///   [MAX_RETRY_DELAY]. It resets to [RETRY_DELAY] after a post that isn't a transient failure.
+    /// * Rate limited posts are reported to `rate_limits`.
+    /// * Requests whose caller has stopped waiting for them, eg after a timeout, are dropped unposted.
+    /// * Returns at once for an empty `input`, without waiting on `pacing`.
     async fn process(
-        mut input: HashMap<String, tokio::sync::oneshot::Sender<T>>,
-        client: &reqwest::Client,
+        mut input: HashMap<String, Responder<T>>,
+        transport: &dyn Transport,
+        payload_template: &str,
+        max_payload_bytes: usize,
+        pacing: &mut Pacing,
+        rate_limits: &broadcast::Sender<RateLimitEvent>,
     ) {
-        if input.is_empty() {
-            return;
-        }
-        let ids: Vec<&str> = input.keys().map(|s| s.as_str()).take(T::max_post_size()).collect();
-        let payload = T::payload_template().replace(r"{ids}", &json::stringify(ids));
-        let values = client
-            .post(String::from(ENSEMBL_SERVER) + T::extension())
-            .header("Content-Type", "application/json")
-            .header("Accept", "application/json")
-            .body(payload)
-            .send()
-            .await
-            .unwrap()
-            .text()
-            .await
-            .unwrap();
-        let outputs: Vec<T> = if let Ok(outputs) = serde_json::from_str(&values) {
-            outputs
-        } else {
-            if let Ok(e) = serde_json::from_str::<EnsemblTopLevelError>(&values) {
-                eprintln!("Ensembl Error: {}", e.error);
-                return;
+        input.retain(|_, responder| !responder.is_closed());
+        while !input.is_empty() {
+            pacing.ready().await;
+            let chunk = next_chunk(
+                &mut input,
+                pacing.post_size(),
+                payload_template,
+                max_payload_bytes,
+            );
+            if chunk.is_empty() {
+                // Every remaining caller gave up while this waited.
+                break;
             }
-            if let Ok(outputs) = serde_json::from_str::<HashMap<String, T>>(&values) {
-                outputs.into_values().collect()
-            } else {
-                panic!("Failed to parse the following response: {}", values);
+            let status = post_chunk(chunk, transport, payload_template).await;
+            if !is_transient(status) {
+                pacing.backoff = RETRY_DELAY;
+                if (200..300).contains(&status) {
+                    pacing.grow();
+                }
+                continue;
             }
-        };
-        for output in outputs.into_iter() {
-            let target = input.remove(output.input()).unwrap();
-            let _ = target.send(output); //if the sender's not listening that's its problem
-        }
-    }
-
-    #[cfg(target_arch = "wasm32")]
-    async fn process(mut input: HashMap<String, tokio::sync::oneshot::Sender<T>>) {
-        if input.is_empty() {
-            return;
-        }
-        let ids: Vec<&str> = input.keys().map(|s| s.as_str()).collect();
-        let payload = T::payload_template().replace(r"{ids}", &json::stringify(ids));
-        let request = ehttp::Request {
-            headers: ehttp::headers(&[
-                ("Content-Type", "application/json"),
-                ("Accept", "application/json"),
-            ]),
-            ..ehttp::Request::post(
-                String::from(ENSEMBL_SERVER) + T::extension(),
-                payload.into(),
-            )
-        };
-        let (tx, resp) = tokio::sync::oneshot::channel();
-        ehttp::fetch(request, move |result| {
-            tx.send(result.unwrap().text().unwrap().to_owned());
-        });
-        let values = resp.await.unwrap();
-        let outputs: Vec<T> = if let Ok(outputs) = serde_json::from_str(&values) {
-            outputs
-        } else {
-            if let Ok(outputs) = serde_json::from_str::<HashMap<String, T>>(&values) {
-                outputs.into_values().collect()
-            } else {
-                panic!("Failed to parse the following response: {}", values);
+            if status == 429 {
+                pacing.shrink();
             }
-        };
-        for output in outputs.into_iter() {
-            let target = input.remove(output.input()).unwrap();
-            let _ = target.send(output); //if the sender's not listening that's it's problem
+            if matches!(status, 429 | 503) {
+                // No receivers is not an error.
+                let _ = rate_limits.send(RateLimitEvent {
+                    status,
+                    wait: pacing.backoff,
+                });
+            }
+            // Nothing is dequeued while paused, so retries queue up and are posted together after.
+            sleep(pacing.backoff).await;
+            pacing.backoff = (pacing.backoff * 2).min(MAX_RETRY_DELAY);
         }
     }
 }
+#[cfg(not(target_arch = "wasm32"))]
 impl<'a, T: 'a + EnsemblPostEndpoint + Send + DeserializeOwned> Getter<T> {
     ///Create a trivially clonable Client that can be sent across async tasks.
     pub fn client(&self) -> Client<'a, T> {
         Client::<T> {
             tx: self.tx.clone(),
+            check_input: self.check_input,
+            negative_cache: self.negative_cache.clone(),
+            task_state: self.guard.task_state.clone(),
             getter: std::marker::PhantomData::<&'a Getter<T>>,
         }
     }
 }
 
+/// Configures a [Getter] before its polling task is spawned.
+/// * Created by the [Getter::builder()] method. [GetterBuilder::build()] spawns the task.
+/// * Endpoint specific options are provided by the endpoint modules, eg [crate::sequence::Flanks].
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone)]
+pub struct GetterBuilder<T: EnsemblPostEndpoint> {
+    payload_template: String,
+    transport: Arc<dyn Transport>,
+    max_requests_per_second: Option<f64>,
+    check_input: InputCheck,
+    negative_cache_ttl: Duration,
+    max_payload_bytes: usize,
+    batch_window: Duration,
+    min_request_spacing: Duration,
+    idle_timeout: Option<Duration>,
+    min_post_size: usize,
+    endpoint: std::marker::PhantomData<T>,
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: EnsemblPostEndpoint> std::fmt::Debug for GetterBuilder<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("GetterBuilder")
+            .field("payload_template", &self.payload_template)
+            .field("max_requests_per_second", &self.max_requests_per_second)
+            .field("negative_cache_ttl", &self.negative_cache_ttl)
+            .field("max_payload_bytes", &self.max_payload_bytes)
+            .field("batch_window", &self.batch_window)
+            .field("min_request_spacing", &self.min_request_spacing)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("min_post_size", &self.min_post_size)
+            .finish_non_exhaustive()
+    }
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: 'static + EnsemblPostEndpoint + Send + DeserializeOwned> GetterBuilder<T> {
+    fn new() -> Self {
+        Self {
+            payload_template: T::payload_template().to_owned(),
+            // Following a redirect would resend the POST as a body-less GET, so surface it instead.
+            transport: Arc::new(
+                reqwest::Client::builder()
+                    .redirect(reqwest::redirect::Policy::none())
+                    .build()
+                    .expect("Failed to build the reqwest client"),
+            ),
+            max_requests_per_second: None,
+            check_input: T::check_input,
+            negative_cache_ttl: Duration::ZERO,
+            max_payload_bytes: MAX_PAYLOAD_BYTES,
+            batch_window: WAIT_DELAY,
+            min_request_spacing: WAIT_DELAY,
+            idle_timeout: None,
+            min_post_size: T::max_post_size(),
+            endpoint: std::marker::PhantomData,
+        }
+    }
+
+    /// Cap the rate of posts to Ensembl, which limits each client to 15 requests per second.
+    /// * Up to one second's worth of requests may be sent in a burst, then posts are spaced to the rate.
+    /// * Applied on top of the batching window and request spacing. Unlimited by default.
+    /// # Panics
+    ///
+    /// Panics if `rate` is not positive.
+    pub fn max_requests_per_second(mut self, rate: f64) -> Self {
+        assert!(rate > 0.0, "max_requests_per_second must be positive");
+        self.max_requests_per_second = Some(rate);
+        self
+    }
+
+    /// Remember identifiers Ensembl returned no result for, answering repeat requests for `ttl`
+    /// without posting them again.
+    /// * Only the "did not return a result" error is cached, never transient or other failures.
+    /// * Zero by default, which disables the cache.
+    pub fn negative_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_cache_ttl = ttl;
+        self
+    }
+
+    /// Cap the size of each posted payload, splitting a batch of long identifiers, eg structural
+    /// variant HGVS notations, into more posts than [EnsemblPostEndpoint::max_post_size] alone would.
+    /// * [MAX_PAYLOAD_BYTES] by default.
+    /// * An identifier too long to fit under the cap on its own is still posted, by itself.
+    pub fn max_payload_bytes(mut self, bytes: usize) -> Self {
+        self.max_payload_bytes = bytes;
+        self
+    }
+
+    /// How long a partial batch waits for more requests before it is posted, [WAIT_DELAY] by default.
+    /// * The window starts when the first request of the batch is queued.
+    /// * A shorter window lowers latency for interactive use, a longer one packs batch jobs into
+    ///   fewer posts.
+    pub fn batch_window(mut self, window: Duration) -> Self {
+        self.batch_window = window;
+        self
+    }
+
+    /// The shortest time between the starts of consecutive posts, [WAIT_DELAY] by default.
+    /// * Also spaces the posts of a batch split into several chunks, and full batches that would
+    ///   otherwise be posted back to back.
+    /// * Zero disables the spacing, leaving only [GetterBuilder::max_requests_per_second].
+    pub fn min_request_spacing(mut self, spacing: Duration) -> Self {
+        self.min_request_spacing = spacing;
+        self
+    }
+
+    /// Shut the polling task down once no request has arrived for `timeout`, eg to free the
+    /// resources of a pooled [Getter] that is no longer used.
+    /// * Requests made after the shutdown fail with an [EnsemblError], and [Client::is_alive] turns
+    ///   false, so a pool can build a replacement.
+    /// * No timeout by default, the task runs as long as any clone of the [Getter].
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Let the number of requests posted together adapt to rate limiting, down to `min`.
+    /// * Each rate limited (429) post halves the size, and each successful one grows it back by a
+    ///   sixteenth of [EnsemblPostEndpoint::max_post_size], see [Getter::effective_post_size].
+    /// * [EnsemblPostEndpoint::max_post_size] by default, which keeps the size fixed.
+    /// # Panics
+    ///
+    /// Panics if `min` is zero or above [EnsemblPostEndpoint::max_post_size].
+    pub fn min_post_size(mut self, min: usize) -> Self {
+        assert!(
+            (1..=T::max_post_size()).contains(&min),
+            "min_post_size must be between 1 and {}",
+            T::max_post_size()
+        );
+        self.min_post_size = min;
+        self
+    }
+
+    /// Post requests through `transport` instead of a default [reqwest::Client].
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Replace the payload template posted to the endpoint, [EnsemblPostEndpoint::payload_template]
+    /// by default.
+    /// * An escape hatch for options this crate doesn't model yet, eg
+    ///   `{"shift_3prime" : 1, "ids" : {ids}}` for VEP.
+    /// * `{ids}` is replaced by the JSON list of the identifiers in each post.
+    /// * Overrides the options set by earlier endpoint specific builder methods, eg
+    ///   [crate::sequence::Flanks], and is overridden by later ones.
+    /// # Panics
+    ///
+    /// Panics unless `payload_template` contains exactly one `{ids}`.
+    pub fn payload_template(mut self, payload_template: impl Into<String>) -> Self {
+        let payload_template = payload_template.into();
+        assert_eq!(
+            payload_template.matches("{ids}").count(),
+            1,
+            "The payload template must contain exactly one {{ids}}: {payload_template}"
+        );
+        self.payload_template = payload_template;
+        self
+    }
+
+    /// Replace the check applied to identifiers before they are queued.
+    /// Defaults to [EnsemblPostEndpoint::check_input].
+    pub(crate) fn check_input(mut self, check_input: InputCheck) -> Self {
+        self.check_input = check_input;
+        self
+    }
+
+    /// Spawn the polling task and return the [Getter] that feeds it.
+    pub fn build(self) -> Getter<T> {
+        let (tx, mut rx) = mpsc::channel::<(String, Responder<T>)>(500);
+        let payload_template = self.payload_template;
+        let check_input = self.check_input;
+        let negative_cache = (!self.negative_cache_ttl.is_zero())
+            .then(|| Arc::new(NegativeCache::new(self.negative_cache_ttl)));
+        let transport = self.transport;
+        let max_payload_bytes = self.max_payload_bytes;
+        let batch_window = self.batch_window;
+        let idle_timeout = self.idle_timeout;
+        let task_state = Arc::new(AtomicU8::new(TASK_ALIVE));
+        let state = task_state.clone();
+        let (rate_limits, _) = broadcast::channel(16);
+        let events = rate_limits.clone();
+        let (paused, paused_rx) = watch::channel(false);
+        let post_size = Arc::new(AtomicUsize::new(T::max_post_size()));
+        let mut pacing = Pacing {
+            post_size: post_size.clone(),
+            min_post_size: self.min_post_size,
+            max_post_size: T::max_post_size(),
+            paused: paused_rx,
+            min_request_spacing: self.min_request_spacing,
+            last_post: None,
+            limiter: self.max_requests_per_second.map(TokenBucket::new),
+            backoff: RETRY_DELAY,
+        };
+        let task = spawn(async move {
+            let mut closed = false;
+            while !closed {
+                let mut gets = HashMap::new();
+                let received = match idle_timeout {
+                    Some(idle_timeout) => tokio::time::timeout(idle_timeout, rx.recv()).await.ok(),
+                    None => Some(rx.recv().await),
+                };
+                let Some(received) = received else {
+                    // Refuse new requests, then answer any that raced the timeout.
+                    state.store(TASK_IDLE, Ordering::Release);
+                    rx.close();
+                    while let Ok((k, v)) = rx.try_recv() {
+                        gets.insert(k, v);
+                    }
+                    Getter::<T>::process(
+                        gets,
+                        transport.as_ref(),
+                        &payload_template,
+                        max_payload_bytes,
+                        &mut pacing,
+                        &events,
+                    )
+                    .await;
+                    break;
+                };
+                let Some((key, value)) = received else {
+                    break;
+                };
+                gets.insert(key, value);
+                // Post as soon as a full chunk is queued, otherwise wait out the batching window.
+                let deadline = tokio::time::Instant::now() + batch_window;
+                while gets.len() < T::max_post_size() {
+                    match tokio::time::timeout_at(deadline, rx.recv()).await {
+                        Ok(Some((k, v))) => {
+                            gets.insert(k, v);
+                        }
+                        Ok(None) => {
+                            closed = true;
+                            break;
+                        }
+                        Err(_) => break,
+                    }
+                }
+                Getter::<T>::process(
+                    gets,
+                    transport.as_ref(),
+                    &payload_template,
+                    max_payload_bytes,
+                    &mut pacing,
+                    &events,
+                )
+                .await;
+            }
+        });
+        Getter {
+            tx,
+            check_input,
+            negative_cache,
+            rate_limits,
+            paused: Arc::new(paused),
+            post_size,
+            guard: Arc::new(TaskGuard { task_state, task }),
+        }
+    }
+}
+
+/// The "no result" errors of a [Getter], each kept for `ttl` after it was received.
+#[derive(Debug)]
+struct NegativeCache {
+    ttl: Duration,
+    entries: std::sync::Mutex<HashMap<String, (tokio::time::Instant, EnsemblError)>>,
+}
+impl NegativeCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, id: &str) -> Option<EnsemblError> {
+        let entries = self.entries.lock().unwrap();
+        let (received, err) = entries.get(id)?;
+        (received.elapsed() < self.ttl).then(|| err.clone())
+    }
+
+    fn insert(&self, err: EnsemblError) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (received, _)| received.elapsed() < self.ttl);
+        entries.insert(err.input.clone(), (tokio::time::Instant::now(), err));
+    }
+}
+
+/// The state a [Getter]'s task keeps between posts to space them out.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+struct Pacing {
+    paused: watch::Receiver<bool>,
+    min_request_spacing: Duration,
+    last_post: Option<tokio::time::Instant>,
+    limiter: Option<TokenBucket>,
+    /// The pause after the next transient failure.
+    backoff: Duration,
+    /// The most requests to post together, shared with the [Getter].
+    post_size: Arc<AtomicUsize>,
+    min_post_size: usize,
+    max_post_size: usize,
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl Pacing {
+    /// Wait until the next post may start: until the [Getter] is resumed, `min_request_spacing`
+    /// has passed since the last post, and a token is available from the `limiter`, if there is one.
+    async fn ready(&mut self) {
+        // The sender lives as long as the Getter, which outlives its task.
+        let _ = self.paused.wait_for(|paused| !*paused).await;
+        if let Some(last_post) = self.last_post {
+            tokio::time::sleep_until(last_post + self.min_request_spacing).await;
+        }
+        if let Some(limiter) = &mut self.limiter {
+            limiter.acquire().await;
+        }
+        self.last_post = Some(tokio::time::Instant::now());
+    }
+
+    fn post_size(&self) -> usize {
+        self.post_size.load(Ordering::Relaxed)
+    }
+
+    /// Halve the post size after a rate limited post, down to `min_post_size`.
+    fn shrink(&self) {
+        let size = (self.post_size() / 2).max(self.min_post_size);
+        self.post_size.store(size, Ordering::Relaxed);
+    }
+
+    /// Grow the post size by a sixteenth of `max_post_size` after a successful post, up to it.
+    fn grow(&self) {
+        let step = (self.max_post_size / 16).max(1);
+        let size = (self.post_size() + step).min(self.max_post_size);
+        self.post_size.store(size, Ordering::Relaxed);
+    }
+}
+
+/// Spaces posts to a maximum rate, allowing bursts of up to one second's worth of requests.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: tokio::time::Instant,
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl TokenBucket {
+    fn new(rate: f64) -> Self {
+        let capacity = rate.max(1.0);
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            last_refill: tokio::time::Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = tokio::time::Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Wait until a token is available, then take it.
+    async fn acquire(&mut self) {
+        self.refill();
+        if self.tokens < 1.0 {
+            sleep(Duration::from_secs_f64((1.0 - self.tokens) / self.rate)).await;
+            self.refill();
+        }
+        self.tokens -= 1.0;
+    }
+}
+
 /// A Client that can be cloned and sent across async tasks or threads to allow access to the underlying [Getter].
 /// * Created by the [Getter::client()] method. [Client::clone()] is equivalent.
 /// * Unlike the [Getter], [Client] implements [Send]. Thus, it is usually created in the parent task then passed to workers.
-#[derive(Debug, Clone)]
+/// * A [Client] doesn't keep the [Getter]'s task alive, see [Client::is_alive].
+#[derive(Debug)]
 pub struct Client<'a, T: EnsemblPostEndpoint + Send + DeserializeOwned> {
-    tx: mpsc::Sender<(String, tokio::sync::oneshot::Sender<T>)>,
+    tx: mpsc::Sender<(String, Responder<T>)>,
+    check_input: InputCheck,
+    negative_cache: Option<Arc<NegativeCache>>,
+    task_state: Arc<AtomicU8>,
     getter: std::marker::PhantomData<&'a Getter<T>>,
 }
+impl<'a, T: EnsemblPostEndpoint + Send + DeserializeOwned> Clone for Client<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            check_input: self.check_input,
+            negative_cache: self.negative_cache.clone(),
+            task_state: self.task_state.clone(),
+            getter: std::marker::PhantomData,
+        }
+    }
+}
 impl<'a, T: 'static + EnsemblPostEndpoint + Send + DeserializeOwned> Client<'a, T> {
+    /// Whether any clone of the [Getter] this client was created from is still alive, and its task
+    /// hasn't shut down after a [GetterBuilder::idle_timeout].
+    pub fn is_alive(&self) -> bool {
+        self.task_state.load(Ordering::Acquire) == TASK_ALIVE
+    }
+
+    /// The error for a request made after the [Getter]'s task shut down for being idle.
+    fn idle_error(&self, id: String) -> EnsemblError {
+        EnsemblError {
+            input: id,
+            error: "The Getter shut down after its idle timeout, build a new one".to_owned(),
+            status_code: 0,
+            retries: 0,
+        }
+    }
+
     /// Get the Ensembl response for the given identifier.
     /// Under the hood, this request will be bundled with other requests then returned asyncronously.
+    /// * Requests rejected with a transient status (429, 503 or 504) are requeued up to [MAX_RETRIES]
+    ///   times. The [Getter] pauses posting after the failure, see [RETRY_DELAY], so every request
+    ///   rejected together is retried together rather than each backing off on its own.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [EnsemblError] if Ensembl reported an error for the request or returned no result for it.
+    /// Requests failing [EnsemblPostEndpoint::check_input], or the check set on the [GetterBuilder],
+    /// are rejected without being queued, as are requests made after a [GetterBuilder::idle_timeout].
     /// # Panics
     ///
     /// Panics if the [Getter] has dropped, or the undelying channel has closed.
-    pub async fn get(self, id: String) -> Option<T> {
+    pub async fn get(self, id: String) -> Result<T, EnsemblError> {
+        for retries in 0..MAX_RETRIES {
+            match self.clone().try_get(id.clone()).await {
+                Err(err) if is_transient(err.status_code) => continue,
+                result => return result.map_err(|err| EnsemblError { retries, ..err }),
+            }
+        }
+        self.try_get(id).await.map_err(|err| EnsemblError {
+            retries: MAX_RETRIES,
+            ..err
+        })
+    }
+
+    /// As [Client::get], but returns the first response without retrying transient failures.
+    /// # Errors
+    ///
+    /// As [Client::get].
+    /// # Panics
+    ///
+    /// As [Client::get].
+    pub async fn try_get(self, id: String) -> Result<T, EnsemblError> {
+        if let Err(error) = (self.check_input)(&id) {
+            return Err(EnsemblError {
+                input: id,
+                error,
+                status_code: 0,
+                retries: 0,
+            });
+        }
+        if let Some(err) = self
+            .negative_cache
+            .as_ref()
+            .and_then(|cache| cache.get(&id))
+        {
+            return Err(err);
+        }
         let (tx, rx) = tokio::sync::oneshot::channel();
-        if let Err(err) = self.tx.send((id, tx)).await {
+        match self.task_state.load(Ordering::Acquire) {
+            TASK_DROPPED => panic!("Getter was dropped before recieving request: {id}"),
+            TASK_IDLE => return Err(self.idle_error(id)),
+            _ => {}
+        }
+        if let Err(err) = self.tx.send((id.clone(), tx)).await {
+            if self.task_state.load(Ordering::Acquire) == TASK_IDLE {
+                return Err(self.idle_error(id));
+            }
             panic!(
                 "Getter was closed or dropped recieving request: {}",
                 err.0 .0
             )
-        }; // If the channel has closed, we can ignore the result
-        rx.await.ok()
+        };
+        let result = rx.await.unwrap_or_else(|_| {
+            Err(EnsemblError {
+                input: id,
+                error: "The request was dropped before a response was received".to_owned(),
+                status_code: 0,
+                retries: 0,
+            })
+        });
+        if let (Err(err), Some(cache)) = (&result, &self.negative_cache) {
+            if err.error == NO_RESULT {
+                cache.insert(err.clone());
+            }
+        }
+        result
+    }
+
+    /// As [Client::get], but gives up if no response arrives within `timeout`.
+    /// * On expiry the request is abandoned and an [EnsemblError] with a `status_code` of 0 is returned.
+    pub async fn get_with_timeout(self, id: String, timeout: Duration) -> Result<T, EnsemblError> {
+        let input = id.clone();
+        tokio::time::timeout(timeout, self.get(id))
+            .await
+            .unwrap_or_else(|_| {
+                Err(EnsemblError {
+                    input,
+                    error: format!("No response was received within {timeout:?}"),
+                    status_code: 0,
+                    retries: 0,
+                })
+            })
+    }
+}
+
+impl<T: 'static + EnsemblPostEndpoint + Send + DeserializeOwned> Client<'static, T> {
+    /// Get the responses for all of `ids` concurrently, as [Client::get], split into the successes
+    /// keyed by input and the errors.
+    /// * Duplicate identifiers are only requested once.
+    /// * Errors are in the order their inputs first appear in `ids`, each names its input in
+    ///   [EnsemblError::input].
+    /// * Returns immediately for empty `ids`, without queueing anything or waiting for the batching window.
+    /// # Panics
+    ///
+    /// As [Client::get].
+    pub async fn get_many_partitioned(
+        &self,
+        ids: impl IntoIterator<Item = String>,
+    ) -> (HashMap<String, T>, Vec<EnsemblError>) {
+        let mut successes = HashMap::new();
+        let mut errors = Vec::new();
+        for (id, result) in self.get_unique(ids).await {
+            match result {
+                Ok(output) => {
+                    successes.insert(id, output);
+                }
+                Err(err) => errors.push(err),
+            }
+        }
+        (successes, errors)
+    }
+
+    /// Get the responses for all of `ids` concurrently, as [Client::get], in the order of `ids`.
+    /// * Duplicate identifiers are only requested once, each occurrence gets a copy of the result.
+    /// * The order doesn't depend on how the requests were batched, so output built from it is
+    ///   reproducible.
+    /// # Panics
+    ///
+    /// As [Client::get].
+    pub async fn get_many(
+        &self,
+        ids: impl IntoIterator<Item = String>,
+    ) -> Vec<Result<T, EnsemblError>>
+    where
+        T: Clone,
+    {
+        let ids: Vec<String> = ids.into_iter().collect();
+        let results: HashMap<String, Result<T, EnsemblError>> = self
+            .get_unique(ids.iter().cloned())
+            .await
+            .into_iter()
+            .collect();
+        ids.iter().map(|id| results[id].clone()).collect()
+    }
+
+    /// Request each of `ids` once, returning the results in the order the inputs first appear.
+    async fn get_unique(
+        &self,
+        ids: impl IntoIterator<Item = String>,
+    ) -> Vec<(String, Result<T, EnsemblError>)> {
+        let mut seen = std::collections::HashSet::new();
+        let handles: Vec<_> = ids
+            .into_iter()
+            .filter(|id| seen.insert(id.clone()))
+            .map(|id| (id.clone(), spawn(self.clone().get(id))))
+            .collect();
+        let mut results = Vec::with_capacity(handles.len());
+        for (id, handle) in handles {
+            match handle.await {
+                Ok(result) => results.push((id, result)),
+                Err(err) => std::panic::resume_unwind(err.into_panic()),
+            }
+        }
+        results
+    }
+}
+
+/// GET `url` and parse the response, for the endpoints that can't be batched by a [Getter].
+/// * Errors are reported against `input`, the identifier or region the request was for.
+pub(crate) async fn get_json<T: DeserializeOwned>(
+    transport: &dyn Transport,
+    url: &str,
+    input: &str,
+) -> Result<T, EnsemblError> {
+    let error = |error: String, status_code: u16| EnsemblError {
+        input: input.to_owned(),
+        error,
+        status_code,
+        retries: 0,
+    };
+    let (status, values) = transport
+        .get(url, HEADERS)
+        .await
+        .map_err(|err| error(format!("Ensembl request failed: {err}"), 0))?;
+    if !(200..300).contains(&status) {
+        return Err(
+            match serde_json::from_str::<EnsemblTopLevelError>(&values) {
+                Ok(e) => error(e.error, status),
+                Err(_) => error(format!("Ensembl returned: {values}"), status),
+            },
+        );
+    }
+    serde_json::from_str(&values).map_err(|err| {
+        error(
+            format!("Failed to parse the response ({err}): {values}"),
+            status,
+        )
+    })
+}
+
+/// Whether a request failing with `status_code` may succeed if it is sent again.
+fn is_transient(status_code: u16) -> bool {
+    matches!(status_code, 429 | 503 | 504)
+}
+
+/// Remove up to `max_post_size` requests from `input` to be posted together,
+/// stopping early if the payload built from `payload_template` would exceed `max_payload_bytes`.
+/// * First drops the requests whose caller has stopped waiting for them, then always removes at
+///   least one of the rest, so an oversized identifier is posted alone.
+fn next_chunk<T: EnsemblPostEndpoint>(
+    input: &mut HashMap<String, Responder<T>>,
+    max_post_size: usize,
+    payload_template: &str,
+    max_payload_bytes: usize,
+) -> HashMap<String, Responder<T>> {
+    // Callers that gave up while their request was queued no longer need it posted.
+    input.retain(|_, responder| !responder.is_closed());
+    // The template without its insertion site, plus the brackets of the id list.
+    let mut payload_bytes = payload_template.len() - "{ids}".len() + 2;
+    let mut ids: Vec<String> = Vec::new();
+    // Sorted, so the same requests are always chunked and posted the same way.
+    for id in input.keys().sorted_unstable().take(max_post_size) {
+        // The quoted, escaped id and its separating comma.
+        let id_bytes = json::stringify(id.as_str()).len() + usize::from(!ids.is_empty());
+        if !ids.is_empty() && payload_bytes + id_bytes > max_payload_bytes {
+            break;
+        }
+        payload_bytes += id_bytes;
+        ids.push(id.clone());
+    }
+    ids.into_iter()
+        .filter_map(|id| input.remove_entry(&id))
+        .collect()
+}
+
+/// Send the same error to every request in `input`.
+fn fail_all<T>(input: HashMap<String, Responder<T>>, error: &str, status_code: u16) {
+    for (id, target) in input.into_iter() {
+        let _ = target.send(Err(EnsemblError {
+            input: id,
+            error: error.to_owned(),
+            status_code,
+            retries: 0,
+        }));
+    }
+}
+
+/// Post a single chunk of requests through `transport` and send each its result.
+/// * Returns the status code of the response, or 0 if no response was received.
+async fn post_chunk<T: EnsemblPostEndpoint + DeserializeOwned>(
+    input: HashMap<String, Responder<T>>,
+    transport: &dyn Transport,
+    payload_template: &str,
+) -> u16 {
+    let ids: Vec<&str> = input.keys().map(|s| s.as_str()).sorted_unstable().collect();
+    let payload = payload_template.replace(r"{ids}", &json::stringify(ids));
+    let url = String::from(ENSEMBL_SERVER) + T::extension();
+    log_debug!("Posting {} ids to {url}: {payload}", input.len());
+    match transport.post(&url, HEADERS, payload).await {
+        Ok((status, values)) => {
+            process_response(input, status, &values);
+            status
+        }
+        Err(err) => {
+            log_warn!("Posting to {url} failed: {err}");
+            fail_all(input, &format!("Ensembl request failed: {err}"), 0);
+            0
+        }
+    }
+}
+
+/// Parse an Ensembl response to the requests in `input` and send each its result.
+/// * Requests without a result, or in a chunk whose response could not be parsed, are sent an [EnsemblError].
+fn process_response<T: EnsemblPostEndpoint + DeserializeOwned>(
+    mut input: HashMap<String, Responder<T>>,
+    status: u16,
+    values: &str,
+) {
+    if (300..400).contains(&status) {
+        log_warn!(
+            "Ensembl redirected {} ids with {status} to {values}",
+            input.len()
+        );
+        let error = format!(
+            "Ensembl redirected the request to `{values}`, which isn't followed; check ENSEMBL_SERVER"
+        );
+        fail_all(input, &error, status);
+        return;
+    }
+    if !(200..300).contains(&status) {
+        if is_transient(status) {
+            log_warn!(
+                "Ensembl returned {status} for {} ids: {values}",
+                input.len()
+            );
+        } else {
+            log_debug!(
+                "Ensembl returned {status} for {} ids: {values}",
+                input.len()
+            );
+        }
+        match serde_json::from_str::<EnsemblTopLevelError>(values) {
+            Ok(e) => fail_all(input, &e.error, status),
+            Err(_) => fail_all(input, &format!("Ensembl returned: {values}"), status),
+        }
+        return;
+    }
+    let outputs: Vec<T> = if let Ok(outputs) = serde_json::from_str(values) {
+        outputs
+    } else {
+        // Ensembl sometimes reports a failure as a 200 with an error object.
+        if let Ok(e) = serde_json::from_str::<EnsemblTopLevelError>(values) {
+            fail_all(input, &e.error, status);
+            return;
+        }
+        match serde_json::from_str::<HashMap<String, T>>(values) {
+            Ok(outputs) => outputs.into_values().collect(),
+            Err(err) => {
+                let error = format!("Failed to parse the response ({err}): {values}");
+                log_error!("{error}");
+                fail_all(input, &error, status);
+                return;
+            }
+        }
+    };
+    for output in outputs.into_iter() {
+        let Some(target) = take_responder(&mut input, output.input()) else {
+            continue;
+        };
+        let _ = target.send(Ok(output)); //if the sender's not listening that's its problem
+    }
+    if !input.is_empty() {
+        log_debug!("Ensembl returned no result for {} ids", input.len());
+    }
+    fail_all(input, NO_RESULT, status);
+}
+
+/// Remove the responder for the request a response with input `returned` answers.
+/// * An exact match wins. Otherwise the first request equal to `returned` once both are normalized
+///   with [normalize_input] is used, eg `ENST00000237014` for a response naming `ENST00000237014.8`.
+fn take_responder<T>(input: &mut HashMap<String, T>, returned: &str) -> Option<T> {
+    if let Some(target) = input.remove(returned) {
+        return Some(target);
+    }
+    let returned = normalize_input(returned);
+    let key = input
+        .keys()
+        .find(|key| normalize_input(key) == returned)?
+        .clone();
+    input.remove(&key)
+}
+
+/// Upper case `id` and strip a trailing version, ie a `.` followed only by digits.
+/// * Only stable identifiers are versioned, so HGVS notations and regions, which contain a `:`, are
+///   unaffected besides their case, eg `18:100..200`.
+fn normalize_input(id: &str) -> String {
+    let id = match id.rsplit_once('.') {
+        Some((stable_id, version))
+            if !id.contains(':')
+                && !version.is_empty()
+                && version.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            stable_id
+        }
+        _ => id,
+    };
+    id.to_ascii_uppercase()
+}
+
+/// The future returned by [Transport::post], resolving to the response status code and body.
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = anyhow::Result<(u16, String)>> + Send + 'a>>;
+
+/// The HTTP layer a [Getter] posts its requests through.
+/// * The default is a [reqwest::Client]. Supply another with [GetterBuilder::transport], eg to serve
+///   canned responses in tests.
+/// * For a redirect (3xx) status the body should be the `Location` redirected to, which the
+///   [Getter] reports rather than follows.
+pub trait Transport: Send + Sync {
+    /// Post `body` to `url` with the given headers.
+    fn post<'a>(
+        &'a self,
+        url: &'a str,
+        headers: &'a [(&'a str, &'a str)],
+        body: String,
+    ) -> TransportFuture<'a>;
+    /// Get `url` with the given headers, for the endpoints that only support GET, eg [crate::overlap].
+    /// * Unsupported unless implemented.
+    /// * A [Getter] only batches posts, so the GET endpoints are requested one identifier or region
+    ///   at a time, through functions taking a [Transport], eg [crate::overlap::overlap_region].
+    fn get<'a>(&'a self, _url: &'a str, _headers: &'a [(&'a str, &'a str)]) -> TransportFuture<'a> {
+        Box::pin(async { anyhow::bail!("GET requests are not supported by this transport") })
+    }
+}
+
+/// With the `gzip` feature enabled, requests ask for gzip compressed responses, which reqwest
+/// decompresses before they are parsed.
+#[cfg(not(target_arch = "wasm32"))]
+impl Transport for reqwest::Client {
+    fn post<'a>(
+        &'a self,
+        url: &'a str,
+        headers: &'a [(&'a str, &'a str)],
+        body: String,
+    ) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let mut request = reqwest::Client::post(self, url).body(body);
+            for (key, value) in headers {
+                request = request.header(*key, *value);
+            }
+            response_body(request.send().await?).await
+        })
+    }
+
+    fn get<'a>(&'a self, url: &'a str, headers: &'a [(&'a str, &'a str)]) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let mut request = reqwest::Client::get(self, url);
+            for (key, value) in headers {
+                request = request.header(*key, *value);
+            }
+            response_body(request.send().await?).await
+        })
+    }
+}
+
+/// The status and body of `response`, or its `Location` header in place of the body for a redirect.
+#[cfg(not(target_arch = "wasm32"))]
+async fn response_body(response: reqwest::Response) -> anyhow::Result<(u16, String)> {
+    let status = response.status();
+    if status.is_redirection() {
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .unwrap_or_default()
+            .to_owned();
+        return Ok((status.as_u16(), location));
+    }
+    Ok((status.as_u16(), response.text().await?))
+}
+
+/// Answers every GET with the same response and records the urls requested, for testing the
+/// functions built on [Transport::get].
+#[cfg(test)]
+pub(crate) struct GetFixture {
+    status: u16,
+    body: &'static str,
+    urls: std::sync::Mutex<Vec<String>>,
+}
+#[cfg(test)]
+impl GetFixture {
+    /// Answer with `body` and a 200 status.
+    pub(crate) fn new(body: &'static str) -> Self {
+        Self::with_status(200, body)
+    }
+
+    pub(crate) fn with_status(status: u16, body: &'static str) -> Self {
+        Self {
+            status,
+            body,
+            urls: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The urls requested so far, in order.
+    pub(crate) fn urls(&self) -> Vec<String> {
+        self.urls.lock().unwrap().clone()
+    }
+}
+#[cfg(test)]
+impl Transport for GetFixture {
+    fn post<'a>(
+        &'a self,
+        url: &'a str,
+        _headers: &'a [(&'a str, &'a str)],
+        _body: String,
+    ) -> TransportFuture<'a> {
+        Box::pin(async move { anyhow::bail!("GetFixture doesn't answer posts, got one to {url}") })
+    }
+
+    fn get<'a>(&'a self, url: &'a str, _headers: &'a [(&'a str, &'a str)]) -> TransportFuture<'a> {
+        self.urls.lock().unwrap().push(url.to_owned());
+        let response = (self.status, self.body.to_owned());
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+/// Serves the same canned response to every post, recording the payloads posted, for testing
+/// the [Getter]s of the POST endpoints.
+#[cfg(test)]
+pub(crate) struct FixtureTransport {
+    status: u16,
+    body: String,
+    payloads: Arc<std::sync::Mutex<Vec<String>>>,
+}
+#[cfg(test)]
+impl FixtureTransport {
+    pub(crate) fn new(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            body: body.to_owned(),
+            payloads: Arc::new(std::sync::Mutex::new(Vec::new())),
+        }
+    }
+}
+#[cfg(test)]
+impl Transport for FixtureTransport {
+    fn post<'a>(
+        &'a self,
+        _url: &'a str,
+        _headers: &'a [(&'a str, &'a str)],
+        body: String,
+    ) -> TransportFuture<'a> {
+        self.payloads.lock().unwrap().push(body);
+        let response = (self.status, self.body.clone());
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+/// The default [Transport] on wasm32, posting through [ehttp::fetch].
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EhttpTransport;
+#[cfg(target_arch = "wasm32")]
+impl Transport for EhttpTransport {
+    fn post<'a>(
+        &'a self,
+        url: &'a str,
+        headers: &'a [(&'a str, &'a str)],
+        body: String,
+    ) -> TransportFuture<'a> {
+        let request = ehttp::Request {
+            headers: ehttp::headers(headers),
+            ..ehttp::Request::post(url, body.into_bytes())
+        };
+        let (tx, resp) = tokio::sync::oneshot::channel();
+        ehttp::fetch(request, move |result| {
+            let _ = tx.send(result.map(|response| {
+                let text = response.text().unwrap_or_default().to_owned();
+                (response.status, text)
+            }));
+        });
+        Box::pin(async move {
+            resp.await?
+                .map_err(|err| anyhow::anyhow!("Failed to fetch: {err}"))
+        })
+    }
+
+    fn get<'a>(&'a self, url: &'a str, headers: &'a [(&'a str, &'a str)]) -> TransportFuture<'a> {
+        let request = ehttp::Request {
+            headers: ehttp::headers(headers),
+            ..ehttp::Request::get(url)
+        };
+        let (tx, resp) = tokio::sync::oneshot::channel();
+        ehttp::fetch(request, move |result| {
+            let _ = tx.send(result.map(|response| {
+                let text = response.text().unwrap_or_default().to_owned();
+                (response.status, text)
+            }));
+        });
+        Box::pin(async move {
+            resp.await?
+                .map_err(|err| anyhow::anyhow!("Failed to fetch: {err}"))
+        })
     }
 }
 
@@ -212,11 +1238,18 @@ pub trait EnsemblPostEndpoint {
     /// Should contain one insertion site for the list of identifiers requested.
     fn payload_template() -> &'static str;
     /// Get the input string from the Ensembl response. Will usually be &self.input
+    /// * Matched back to the request exactly if possible, otherwise ignoring case and a trailing
+    ///   version, so a response for `ENST00000237014.8` answers a request for `ENST00000237014`.
     fn input(&self) -> &str;
     // Get the maximum number of identifiers that can be sent in a single request.
     fn max_post_size() -> usize {
         50
     }
+    /// Check an identifier before it is queued so malformed input is rejected locally.
+    /// Accepts everything by default.
+    fn check_input(_input: &str) -> Result<(), String> {
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
@@ -224,25 +1257,63 @@ pub struct EnsemblTopLevelError {
     pub error: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+/// An error returned in place of a result for a single input.
+/// * `status_code` is the HTTP status of the response, or 0 if no response was received.
+/// * `retries` is how many times [Client::get] retried the request before giving up, 0 if it
+///   failed on the first attempt.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq, thiserror::Error)]
+#[error("{input}: {error}")]
 pub struct EnsemblError {
     pub input: String,
     pub error: String,
+    #[serde(default)]
+    pub status_code: u16,
+    #[serde(default)]
+    pub retries: u32,
 }
 
 #[cfg(target_arch = "wasm32")]
 pub struct Getter<T: EnsemblPostEndpoint + DeserializeOwned> {
-    tx: mpsc::Sender<(String, tokio::sync::oneshot::Sender<T>)>,
-    rx: mpsc::Receiver<(String, tokio::sync::oneshot::Sender<T>)>,
+    tx: mpsc::Sender<(String, Responder<T>)>,
+    rx: mpsc::Receiver<(String, Responder<T>)>,
     last_fetch: std::time::Instant,
+    /// Shared with the [Client]s, as the native [TaskGuard]'s.
+    task_state: Arc<AtomicU8>,
     //to_fetch: HashMap<String, Sender<T>>,
 }
 #[cfg(target_arch = "wasm32")]
+impl<T: EnsemblPostEndpoint + DeserializeOwned> Drop for Getter<T> {
+    fn drop(&mut self) {
+        self.task_state.store(TASK_DROPPED, Ordering::Release);
+    }
+}
+#[cfg(target_arch = "wasm32")]
 impl<T: 'static + EnsemblPostEndpoint + DeserializeOwned> Getter<T> {
     pub fn new() -> Self {
         let (tx, rx) = mpsc::channel(500);
         let last_fetch = std::time::Instant::now();
-        Self { tx, rx, last_fetch }
+        Self {
+            tx,
+            rx,
+            last_fetch,
+            task_state: Arc::new(AtomicU8::new(TASK_ALIVE)),
+        }
+    }
+
+    ///Create a trivially clonable Client that can be sent across async tasks.
+    /// * Requests are checked with [EnsemblPostEndpoint::check_input] and "no result" errors aren't
+    ///   cached, as the wasm [Getter] has no builder.
+    pub fn client<'a>(&self) -> Client<'a, T>
+    where
+        T: 'a + Send,
+    {
+        Client::<T> {
+            tx: self.tx.clone(),
+            check_input: T::check_input,
+            negative_cache: None,
+            task_state: self.task_state.clone(),
+            getter: std::marker::PhantomData::<&'a Getter<T>>,
+        }
     }
 
     pub async fn process(&mut self) {
@@ -258,38 +1329,744 @@ impl<T: 'static + EnsemblPostEndpoint + DeserializeOwned> Getter<T> {
         while let Ok((k, v)) = self.rx.try_recv() {
             input.insert(k, v);
         }
-        if input.is_empty() {
-            return;
+        while !input.is_empty() {
+            let chunk = next_chunk(
+                &mut input,
+                T::max_post_size(),
+                T::payload_template(),
+                MAX_PAYLOAD_BYTES,
+            );
+            if chunk.is_empty() {
+                break;
+            }
+            post_chunk(chunk, &EhttpTransport, T::payload_template()).await;
         }
-        let ids: Vec<&str> = input.keys().map(|s| s.as_str()).collect();
-        let payload = T::payload_template().replace(r"{ids}", &json::stringify(ids));
-        let request = ehttp::Request {
-            headers: ehttp::headers(&[
-                ("Content-Type", "application/json"),
-                ("Accept", "application/json"),
-            ]),
-            ..ehttp::Request::post(
-                String::from(ENSEMBL_SERVER) + T::extension(),
-                payload.into(),
-            )
-        };
-        let (tx, resp) = tokio::sync::oneshot::channel();
-        ehttp::fetch(request, move |result| {
-            tx.send(result.unwrap().text().unwrap().to_owned());
-        });
-        let values = resp.await.unwrap();
-        let outputs: Vec<T> = if let Ok(outputs) = serde_json::from_str(&values) {
-            outputs
-        } else {
-            if let Ok(outputs) = serde_json::from_str::<HashMap<String, T>>(&values) {
-                outputs.into_values().collect()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::sequence::CdnaSequence;
+    use std::sync::Mutex;
+
+    /// Never responds.
+    struct HangingTransport;
+    impl Transport for HangingTransport {
+        fn post<'a>(
+            &'a self,
+            _url: &'a str,
+            _headers: &'a [(&'a str, &'a str)],
+            _body: String,
+        ) -> TransportFuture<'a> {
+            Box::pin(std::future::pending())
+        }
+    }
+
+    const CDNA_JSON: &str =
+        r#"[{"query":"ENST00000237014","id":"ENST00000237014.8","desc":null,"seq":"ACGT"}]"#;
+
+    #[tokio::test]
+    async fn test_transport_success() {
+        let transport = FixtureTransport::new(200, CDNA_JSON);
+        let payloads = transport.payloads.clone();
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(transport)
+            .build();
+        let cdna = getter.client().get("ENST00000237014".to_owned()).await;
+        assert_eq!(cdna.unwrap().seq, "ACGT");
+        assert!(payloads.lock().unwrap()[0].contains(r#"["ENST00000237014"]"#));
+    }
+
+    #[tokio::test]
+    async fn test_unparseable_response() {
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(FixtureTransport::new(200, "<html>Gateway Timeout</html>"))
+            .build();
+        let handles: Vec<_> = ["ENST00000237014", "ENST00000380518"]
+            .into_iter()
+            .map(|id| {
+                let client = getter.client();
+                tokio::spawn(async move { client.get(id.to_owned()).await })
+            })
+            .collect();
+        for handle in handles {
+            let err = handle.await.unwrap().unwrap_err();
+            assert!(err.error.contains("<html>Gateway Timeout</html>"));
+        }
+        // The polling task survives to serve later requests.
+        let err = getter
+            .client()
+            .get("ENST00000237014".to_owned())
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code, 200);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_token_bucket() {
+        let start = tokio::time::Instant::now();
+        let mut bucket = TokenBucket::new(2.0);
+        bucket.acquire().await;
+        bucket.acquire().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        bucket.acquire().await;
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+        bucket.acquire().await;
+        assert_eq!(start.elapsed(), Duration::from_millis(1000));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flush_when_full() {
+        let transport = FixtureTransport::new(200, "[]");
+        let payloads = transport.payloads.clone();
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(transport)
+            .build();
+        let start = tokio::time::Instant::now();
+        let handles: Vec<_> = (0..CdnaSequence::max_post_size())
+            .map(|i| {
+                let client = getter.client();
+                tokio::spawn(async move { client.get(format!("ENST{i:011}")).await })
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.await.unwrap().is_err());
+        }
+        assert!(start.elapsed() < WAIT_DELAY);
+        // A partial batch still waits for the batching window.
+        let _ = getter.client().get("ENST00000237014".to_owned()).await;
+        assert!(start.elapsed() >= WAIT_DELAY);
+        assert_eq!(payloads.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_batch_deadline() {
+        let transport = FixtureTransport::new(200, "[]");
+        let payloads = transport.payloads.clone();
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(transport)
+            .build();
+        // The first request starts the window, later arrivals join it without extending it.
+        let start = tokio::time::Instant::now();
+        let first = tokio::spawn(getter.client().get("ENST00000000001".to_owned()));
+        sleep(WAIT_DELAY / 2).await;
+        let second = tokio::spawn(getter.client().get("ENST00000000002".to_owned()));
+        let _ = first.await;
+        let _ = second.await;
+        assert_eq!(start.elapsed(), WAIT_DELAY);
+        assert_eq!(payloads.lock().unwrap().len(), 1);
+        // After an idle period a new request waits one window, not the remainder of a fixed sleep.
+        sleep(WAIT_DELAY * 3 + WAIT_DELAY / 3).await;
+        let start = tokio::time::Instant::now();
+        let _ = getter.client().get("ENST00000000003".to_owned()).await;
+        assert_eq!(start.elapsed(), WAIT_DELAY);
+        assert_eq!(payloads.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_max_requests_per_second() {
+        let transport = FixtureTransport::new(200, "[]");
+        let payloads = transport.payloads.clone();
+        let start = tokio::time::Instant::now();
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(transport)
+            .max_requests_per_second(1.0)
+            .build();
+        // Three full chunks queued at once are spread over the following seconds.
+        let handles: Vec<_> = (0..3 * CdnaSequence::max_post_size())
+            .map(|i| {
+                let client = getter.client();
+                tokio::spawn(async move { client.get(format!("ENST{i:011}")).await })
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.await.unwrap().is_err());
+        }
+        assert_eq!(payloads.lock().unwrap().len(), 3);
+        assert!(start.elapsed() >= Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_get_with_timeout() {
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(HangingTransport)
+            .build();
+        let start = tokio::time::Instant::now();
+        let err = getter
+            .client()
+            .get_with_timeout("ENST00000237014".to_owned(), Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert_eq!(err.input, "ENST00000237014");
+        assert_eq!(err.status_code, 0);
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_clone_keeps_task_alive() {
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(FixtureTransport::new(200, CDNA_JSON))
+            .build();
+        let clone = getter.clone();
+        let client = getter.client();
+        drop(getter);
+        assert!(client.is_alive());
+        let cdna = client.get("ENST00000237014".to_owned()).await.unwrap();
+        assert_eq!(cdna.seq, "ACGT");
+
+        let hanging = Getter::<CdnaSequence>::builder()
+            .transport(HangingTransport)
+            .build();
+        let client = hanging.client();
+        let request = tokio::spawn(client.clone().get("ENST00000237014".to_owned()));
+        tokio::task::yield_now().await;
+        drop(hanging);
+        let err = request.await.unwrap().unwrap_err();
+        assert_eq!(err.status_code, 0);
+        assert!(!client.is_alive());
+        assert!(clone.client().is_alive());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_timeout() {
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(FixtureTransport::new(200, CDNA_JSON))
+            .idle_timeout(Duration::from_secs(60))
+            .build();
+        let client = getter.client();
+        // Each request restarts the idle period.
+        for _ in 0..3 {
+            sleep(Duration::from_secs(40)).await;
+            let cdna = client.clone().get("ENST00000237014".to_owned()).await;
+            assert_eq!(cdna.unwrap().seq, "ACGT");
+        }
+        assert!(client.is_alive());
+        sleep(Duration::from_secs(61)).await;
+        assert!(!client.is_alive());
+        let err = client
+            .clone()
+            .get("ENST00000237014".to_owned())
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code, 0);
+        assert!(err.error.contains("idle timeout"), "{}", err.error);
+
+        // Without a timeout the task outlives any idle period.
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(FixtureTransport::new(200, CDNA_JSON))
+            .build();
+        sleep(Duration::from_secs(3600)).await;
+        assert!(getter.client().is_alive());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retries() {
+        for (status, posts) in [(400, 1), (429, 4), (503, 4)] {
+            let transport = FixtureTransport::new(status, r#"{"error":"Something went wrong"}"#);
+            let payloads = transport.payloads.clone();
+            let getter = Getter::<CdnaSequence>::builder()
+                .transport(transport)
+                .build();
+            let start = tokio::time::Instant::now();
+            let err = getter
+                .client()
+                .get("ENST00000237014".to_owned())
+                .await
+                .unwrap_err();
+            assert_eq!(payloads.lock().unwrap().len(), posts);
+            assert_eq!(err.retries, posts as u32 - 1);
+            if posts > 1 {
+                assert!(start.elapsed() >= RETRY_DELAY * 7);
+            }
+            payloads.lock().unwrap().clear();
+            let err = getter
+                .client()
+                .try_get("ENST00000237014".to_owned())
+                .await
+                .unwrap_err();
+            assert_eq!(err.status_code, status);
+            assert_eq!(err.retries, 0);
+            assert_eq!(payloads.lock().unwrap().len(), 1);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_negative_cache() {
+        let transport = FixtureTransport::new(200, "[]");
+        let payloads = transport.payloads.clone();
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(transport)
+            .negative_cache_ttl(Duration::from_secs(60))
+            .build();
+        for _ in 0..3 {
+            let err = getter
+                .client()
+                .get("ENST00000000000".to_owned())
+                .await
+                .unwrap_err();
+            assert_eq!(err.error, NO_RESULT);
+        }
+        assert_eq!(payloads.lock().unwrap().len(), 1);
+        sleep(Duration::from_secs(61)).await;
+        getter
+            .client()
+            .get("ENST00000000000".to_owned())
+            .await
+            .unwrap_err();
+        assert_eq!(payloads.lock().unwrap().len(), 2);
+
+        // Transient errors are never cached.
+        let transport = FixtureTransport::new(503, r#"{"error":"Service unavailable"}"#);
+        let payloads = transport.payloads.clone();
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(transport)
+            .negative_cache_ttl(Duration::from_secs(60))
+            .build();
+        for _ in 0..2 {
+            getter
+                .client()
+                .try_get("ENST00000000000".to_owned())
+                .await
+                .unwrap_err();
+        }
+        assert_eq!(payloads.lock().unwrap().len(), 2);
+    }
+
+    /// Fails every post with `failures` before succeeding.
+    struct FlakyTransport {
+        failures: Mutex<Vec<u16>>,
+        posts: Arc<Mutex<Vec<(tokio::time::Instant, String)>>>,
+    }
+    impl Transport for FlakyTransport {
+        fn post<'a>(
+            &'a self,
+            _url: &'a str,
+            _headers: &'a [(&'a str, &'a str)],
+            body: String,
+        ) -> TransportFuture<'a> {
+            self.posts
+                .lock()
+                .unwrap()
+                .push((tokio::time::Instant::now(), body));
+            let response = match self.failures.lock().unwrap().pop() {
+                Some(status) => (status, r#"{"error":"Too many requests"}"#.to_owned()),
+                None => (200, "[]".to_owned()),
+            };
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shared_backoff() {
+        let posts = Arc::new(Mutex::new(Vec::new()));
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(FlakyTransport {
+                failures: Mutex::new(vec![429, 429]),
+                posts: posts.clone(),
+            })
+            .build();
+        let mut events = getter.rate_limit_events();
+        let start = tokio::time::Instant::now();
+        let handles: Vec<_> = (0..20)
+            .map(|i| tokio::spawn(getter.client().get(format!("ENST{i:011}"))))
+            .collect();
+        for handle in handles {
+            let err = handle.await.unwrap().unwrap_err();
+            assert_eq!((err.error.as_str(), err.retries), (NO_RESULT, 2));
+        }
+        // Every request is retried in the same post, after the Getter's pause and batching window.
+        let posts = posts.lock().unwrap();
+        let times: Vec<_> = posts.iter().map(|(time, _)| *time - start).collect();
+        assert_eq!(
+            times,
+            [
+                WAIT_DELAY,
+                WAIT_DELAY * 2 + RETRY_DELAY,
+                WAIT_DELAY * 3 + RETRY_DELAY * 3
+            ]
+        );
+        assert!(posts
+            .iter()
+            .all(|(_, body)| body.matches("ENST").count() == 20));
+        assert_eq!(events.try_recv().unwrap().wait, RETRY_DELAY);
+        assert_eq!(events.try_recv().unwrap().wait, RETRY_DELAY * 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_events() {
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(FixtureTransport::new(
+                429,
+                r#"{"error":"Too many requests"}"#,
+            ))
+            .build();
+        let mut events = getter.rate_limit_events();
+        let err = getter
+            .client()
+            .try_get("ENST00000237014".to_owned())
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code, 429);
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.status, 429);
+        assert_eq!(event.wait, RETRY_DELAY);
+
+        // Other failures aren't reported.
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(FixtureTransport::new(400, r#"{"error":"Bad request"}"#))
+            .build();
+        let mut events = getter.rate_limit_events();
+        getter
+            .client()
+            .get("ENST00000237014".to_owned())
+            .await
+            .unwrap_err();
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_many_partitioned() {
+        let transport = FixtureTransport::new(200, CDNA_JSON);
+        let payloads = transport.payloads.clone();
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(transport)
+            .build();
+        let ids = ["ENST00000237014", "ENST00000000000", "ENST00000237014", ""];
+        let (successes, errors) = getter
+            .client()
+            .get_many_partitioned(ids.map(str::to_owned))
+            .await;
+        assert_eq!(successes.len(), 1);
+        assert_eq!(successes["ENST00000237014"].seq, "ACGT");
+        let inputs: Vec<_> = errors.iter().map(|err| err.input.as_str()).collect();
+        assert_eq!(inputs, ["ENST00000000000", ""]);
+        assert_eq!(errors[0].error, NO_RESULT);
+        assert_eq!(payloads.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_many_in_order() {
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(FixtureTransport::new(200, CDNA_JSON))
+            .build();
+        let ids = [
+            "ENST00000380518",
+            "ENST00000237014",
+            "ENST00000000000",
+            "ENST00000237014",
+        ];
+        for _ in 0..3 {
+            let results = getter.client().get_many(ids.map(str::to_owned)).await;
+            let outputs: Vec<_> = results
+                .iter()
+                .map(|result| match result {
+                    Ok(cdna) => cdna.query.as_str(),
+                    Err(err) => err.input.as_str(),
+                })
+                .collect();
+            assert_eq!(outputs, ids);
+            assert!(results[0].is_err() && results[1].is_ok() && results[3].is_ok());
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_get_many_empty() {
+        let transport = FixtureTransport::new(200, CDNA_JSON);
+        let payloads = transport.payloads.clone();
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(transport)
+            .build();
+        let start = tokio::time::Instant::now();
+        let (successes, errors) = getter.client().get_many_partitioned(Vec::new()).await;
+        assert!(successes.is_empty() && errors.is_empty());
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        assert!(payloads.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_versioned_response() {
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(FixtureTransport::new(
+                200,
+                r#"[{"query":"ENST00000237014.8","id":"ENST00000237014.8","desc":null,"seq":"ACGT"},
+                    {"query":"enst00000380518","id":"ENST00000380518.3","desc":null,"seq":"TTTT"}]"#,
+            ))
+            .build();
+        let ids = ["ENST00000237014", "ENST00000380518.3", "ENST00000000000"];
+        let results = getter.client().get_many(ids.map(str::to_owned)).await;
+        assert_eq!(results[0].as_ref().unwrap().seq, "ACGT");
+        assert_eq!(results[1].as_ref().unwrap().seq, "TTTT");
+        assert_eq!(results[2].as_ref().unwrap_err().error, NO_RESULT);
+        assert_eq!(normalize_input("18:g.31592974G>A"), "18:G.31592974G>A");
+        assert_eq!(normalize_input("ENSP00000237014."), "ENSP00000237014.");
+        assert_eq!(normalize_input("18:100..200"), "18:100..200");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pause() {
+        let transport = FixtureTransport::new(200, CDNA_JSON);
+        let payloads = transport.payloads.clone();
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(transport)
+            .build();
+        getter.pause();
+        assert!(getter.clone().is_paused());
+        let client = getter.client();
+        let handle = tokio::spawn(async move { client.get("ENST00000237014".to_owned()).await });
+        sleep(Duration::from_secs(60)).await;
+        assert!(payloads.lock().unwrap().is_empty());
+        assert!(!handle.is_finished());
+        getter.resume();
+        assert!(!getter.is_paused());
+        assert_eq!(handle.await.unwrap().unwrap().seq, "ACGT");
+        assert_eq!(payloads.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_max_payload_bytes() {
+        let transport = FixtureTransport::new(200, CDNA_JSON);
+        let payloads = transport.payloads.clone();
+        // Room for the template and two 17 byte ids, but not three.
+        let template_bytes = CdnaSequence::payload_template().len() - "{ids}".len() + 2;
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(transport)
+            .max_payload_bytes(template_bytes + 17 * 2 + 1)
+            .build();
+        let ids = [
+            "ENST00000237014",
+            "ENST00000380518",
+            "ENST00000000001",
+            "ENST00000000002",
+            "ENST00000000003",
+        ];
+        getter.client().get_many(ids.map(str::to_owned)).await;
+        let payloads = payloads.lock().unwrap();
+        assert_eq!(payloads.len(), 3);
+        assert!(payloads
+            .iter()
+            .all(|p| p.len() <= template_bytes + 17 * 2 + 1));
+        assert_eq!(
+            payloads
+                .iter()
+                .map(|p| p.matches("ENST").count())
+                .sum::<usize>(),
+            5
+        );
+
+        let mut input = HashMap::new();
+        let (tx, _rx) = tokio::sync::oneshot::channel::<Result<CdnaSequence, EnsemblError>>();
+        input.insert("ENST00000237014".to_owned(), tx);
+        let chunk = next_chunk(&mut input, 50, CdnaSequence::payload_template(), 1);
+        assert_eq!(chunk.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_custom_payload_template() {
+        let transport = FixtureTransport::new(200, CDNA_JSON);
+        let payloads = transport.payloads.clone();
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(transport)
+            .payload_template(r#"{"type" : "cdna", "mask_feature" : 1, "ids" : {ids}}"#)
+            .build();
+        getter
+            .client()
+            .get("ENST00000237014".to_owned())
+            .await
+            .unwrap();
+        assert_eq!(
+            payloads.lock().unwrap()[0],
+            r#"{"type" : "cdna", "mask_feature" : 1, "ids" : ["ENST00000237014"]}"#
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exactly one {ids}")]
+    fn test_payload_template_without_ids() {
+        let _ = Getter::<CdnaSequence>::builder().payload_template(r#"{"ids" : []}"#);
+    }
+
+    #[test]
+    #[should_panic(expected = "exactly one {ids}")]
+    fn test_payload_template_with_two_ids() {
+        let _ =
+            Getter::<CdnaSequence>::builder().payload_template(r#"{"ids" : {ids}, "x" : {ids}}"#);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cancelled_requests_skipped() {
+        let transport = FixtureTransport::new(200, "[]");
+        let payloads = transport.payloads.clone();
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(transport)
+            .build();
+        let handles: Vec<_> = (0..10)
+            .map(|i| tokio::spawn(getter.client().get(format!("ENST{i:011}"))))
+            .collect();
+        sleep(WAIT_DELAY / 2).await;
+        let mut live = Vec::new();
+        for (i, handle) in handles.into_iter().enumerate() {
+            if i % 2 == 0 {
+                handle.abort();
             } else {
-                panic!("Failed to parse the following response: {}", values);
+                live.push(handle);
             }
-        };
-        for output in outputs.into_iter() {
-            let target = input.remove(output.input()).unwrap();
-            let _ = target.send(output); //if the sender's not listening that's it's problem
         }
+        for handle in live {
+            assert_eq!(handle.await.unwrap().unwrap_err().error, NO_RESULT);
+        }
+        {
+            let payloads = payloads.lock().unwrap();
+            assert_eq!(payloads.len(), 1);
+            assert_eq!(payloads[0].matches("ENST").count(), 5);
+            assert!(!payloads[0].contains("ENST00000000000"));
+            assert!(payloads[0].contains("ENST00000000001"));
+        }
+        // Nothing is posted when every caller gave up.
+        let cancelled = tokio::spawn(getter.client().get("ENST00000000010".to_owned()));
+        sleep(WAIT_DELAY / 2).await;
+        cancelled.abort();
+        sleep(WAIT_DELAY * 2).await;
+        assert_eq!(payloads.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_adaptive_post_size() {
+        let posts = Arc::new(Mutex::new(Vec::new()));
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(FlakyTransport {
+                failures: Mutex::new(vec![429, 429, 429]),
+                posts: posts.clone(),
+            })
+            .min_post_size(10)
+            .build();
+        assert_eq!(getter.effective_post_size(), 50);
+        let ids: Vec<_> = (0..50).map(|i| format!("ENST{i:011}")).collect();
+        getter.client().get_many(ids).await;
+        // Halved by each 429 down to the minimum, then grown back by each successful post.
+        let sizes: Vec<_> = posts
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, body)| body.matches("ENST").count())
+            .collect();
+        assert_eq!(&sizes[..3], [50, 25, 12]);
+        assert_eq!(sizes[3], 10);
+        assert_eq!(sizes.iter().skip(3).sum::<usize>(), 50);
+        assert!(getter.effective_post_size() > 10);
+        assert!(getter.effective_post_size() <= 50);
+
+        let fixed = Getter::<CdnaSequence>::builder()
+            .transport(FlakyTransport {
+                failures: Mutex::new(vec![429]),
+                posts: Arc::new(Mutex::new(Vec::new())),
+            })
+            .build();
+        let _ = fixed.client().get("ENST00000237014".to_owned()).await;
+        assert_eq!(fixed.effective_post_size(), 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "min_post_size must be between 1 and 50")]
+    fn test_min_post_size_above_max() {
+        let _ = Getter::<CdnaSequence>::builder().min_post_size(51);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_batch_window_and_spacing() {
+        let posts = Arc::new(Mutex::new(Vec::new()));
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(FlakyTransport {
+                failures: Mutex::new(Vec::new()),
+                posts: posts.clone(),
+            })
+            .batch_window(Duration::from_millis(100))
+            .min_request_spacing(Duration::from_secs(2))
+            .build();
+        let start = tokio::time::Instant::now();
+        let handles: Vec<_> = (0..CdnaSequence::max_post_size() + 1)
+            .map(|i| tokio::spawn(getter.client().get(format!("ENST{i:011}"))))
+            .collect();
+        for handle in handles {
+            let _ = handle.await;
+        }
+        // The full batch is posted at once, the remainder waits out its window and the spacing.
+        let times: Vec<_> = posts
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(time, _)| *time - start)
+            .collect();
+        assert_eq!(times, [Duration::ZERO, Duration::from_secs(2)]);
+        sleep(Duration::from_secs(10)).await;
+        let start = tokio::time::Instant::now();
+        let _ = getter.client().get("ENST00000000001".to_owned()).await;
+        assert_eq!(start.elapsed(), Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_redirect() {
+        let transport = FixtureTransport::new(302, "https://mirror.example.org/sequence/id");
+        let payloads = transport.payloads.clone();
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(transport)
+            .build();
+        let err = getter
+            .client()
+            .get("ENST00000237014".to_owned())
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code, 302);
+        assert!(err
+            .error
+            .contains("`https://mirror.example.org/sequence/id`"));
+        // Redirects aren't transient, so the request isn't retried.
+        assert_eq!(err.retries, 0);
+        assert_eq!(payloads.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_missing_result() {
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(FixtureTransport::new(200, "[]"))
+            .build();
+        let err = getter
+            .client()
+            .get("ENST00000000000".to_owned())
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code, 200);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_transport_error_status() {
+        for status in [400, 403, 429, 503] {
+            let transport = FixtureTransport::new(status, r#"{"error":"Something went wrong"}"#);
+            let getter = Getter::<CdnaSequence>::builder()
+                .transport(transport)
+                .build();
+            let err = getter
+                .client()
+                .get("ENST00000237014".to_owned())
+                .await
+                .unwrap_err();
+            assert_eq!(err.status_code, status);
+            assert_eq!(err.error, "Something went wrong");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_object_with_ok_status() {
+        let body = r#"{"error":"Could not find any variants for the given input"}"#;
+        let getter = Getter::<CdnaSequence>::builder()
+            .transport(FixtureTransport::new(200, body))
+            .build();
+        let err = getter
+            .client()
+            .get("ENST00000237014".to_owned())
+            .await
+            .unwrap_err();
+        assert_eq!(err.input, "ENST00000237014");
+        assert_eq!(err.error, "Could not find any variants for the given input");
     }
 }
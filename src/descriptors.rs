@@ -4,17 +4,15 @@ use std::fmt;
 use std::str::FromStr;
 use thiserror::Error;
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Serialize, Deserialize)]
-#[serde(from = "i32")]
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(from = "i32", into = "i32")]
 pub enum Canonical {
     CANONICAL,
+    #[default]
     NONCANONICAL,
 }
-impl Default for Canonical {
-    fn default() -> Self {
-        Self::NONCANONICAL
-    }
-}
 impl From<i32> for Canonical {
     fn from(value: i32) -> Self {
         match value {
@@ -23,8 +21,62 @@ impl From<i32> for Canonical {
         }
     }
 }
+impl From<Canonical> for i32 {
+    fn from(value: Canonical) -> Self {
+        match value {
+            Canonical::CANONICAL => 1,
+            Canonical::NONCANONICAL => 0,
+        }
+    }
+}
+
+/// Serialize [Canonical] as a boolean rather than the `1`/`0` Ensembl uses.
+/// * Use with `#[serde(with = "rs_embl::canonical_as_bool")]`.
+/// * Deserializing accepts both booleans and integers.
+/// ```
+/// use rs_embl::Canonical;
+/// use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Report {
+///     #[serde(with = "rs_embl::canonical_as_bool")]
+///     canonical: Canonical,
+/// }
+/// let report: Report = serde_json::from_str(r#"{"canonical": 1}"#).unwrap();
+/// assert_eq!(report.canonical, Canonical::CANONICAL);
+/// assert_eq!(serde_json::to_string(&report).unwrap(), r#"{"canonical":true}"#);
+/// ```
+pub mod canonical_as_bool {
+    use super::Canonical;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(canonical: &Canonical, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bool(*canonical == Canonical::CANONICAL)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Canonical, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum BoolOrInt {
+            Bool(bool),
+            Int(i32),
+        }
+        Ok(match BoolOrInt::deserialize(deserializer)? {
+            BoolOrInt::Bool(true) => Canonical::CANONICAL,
+            BoolOrInt::Bool(false) => Canonical::NONCANONICAL,
+            BoolOrInt::Int(value) => Canonical::from(value),
+        })
+    }
+}
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+/// The strand of a feature, serialized as Ensembl's `1` or `-1`.
+/// * Deserializes from `1`/`-1` or `"+"`/`"-"`.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Strand {
     PLUS,
     MINUS,
@@ -39,6 +91,22 @@ impl TryFrom<i32> for Strand {
         }
     }
 }
+impl From<Strand> for i8 {
+    fn from(value: Strand) -> Self {
+        match value {
+            Strand::PLUS => 1,
+            Strand::MINUS => -1,
+        }
+    }
+}
+impl Serialize for Strand {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_i8(i8::from(*self))
+    }
+}
 impl FromStr for Strand {
     type Err = StrandError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -77,7 +145,9 @@ pub enum StrandError {
     InvalidStrandNumber(i32),
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[derive(
+    Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
 #[allow(non_camel_case_types)]
 pub enum Biotype {
     TR_V_gene,
@@ -140,15 +210,263 @@ pub enum Biotype {
     processed_transcript,
     IG_V_gene,
     IG_C_gene,
+    #[default]
+    Unknown,
+}
+impl Biotype {
+    /// Whether transcripts of this biotype encode a protein, including the immunoglobulin and
+    /// T cell receptor gene segments and the protein coding transcripts targeted for decay.
+    pub fn is_coding(&self) -> bool {
+        matches!(
+            self,
+            Biotype::protein_coding
+                | Biotype::protein_coding_LoF
+                | Biotype::nonsense_mediated_decay
+                | Biotype::non_stop_decay
+                | Biotype::IG_C_gene
+                | Biotype::IG_D_gene
+                | Biotype::IG_J_gene
+                | Biotype::IG_V_gene
+                | Biotype::TR_C_gene
+                | Biotype::TR_D_gene
+                | Biotype::TR_J_gene
+                | Biotype::TR_V_gene
+        )
+    }
+
+    /// Whether this is any of the pseudogene biotypes.
+    pub fn is_pseudogene(&self) -> bool {
+        matches!(
+            self,
+            Biotype::pseudogene
+                | Biotype::processed_pseudogene
+                | Biotype::unprocessed_pseudogene
+                | Biotype::unitary_pseudogene
+                | Biotype::transcribed_pseudogene
+                | Biotype::transcribed_processed_pseudogene
+                | Biotype::transcribed_unitary_pseudogene
+                | Biotype::transcribed_unprocessed_pseudogen
+                | Biotype::translated_processed_pseudogene
+                | Biotype::ncRNA_pseudogene
+                | Biotype::rRNA_pseudogene
+                | Biotype::IG_pseudogene
+                | Biotype::IG_C_pseudogene
+                | Biotype::IG_J_pseudogene
+                | Biotype::IG_V_pseudogene
+                | Biotype::TR_J_pseudogene
+                | Biotype::TR_V_pseudogene
+        )
+    }
+
+    /// Whether this is a functional non-coding RNA biotype, long or small.
+    pub fn is_noncoding_rna(&self) -> bool {
+        matches!(
+            self,
+            Biotype::lncRNA
+                | Biotype::antisense_RNA
+                | Biotype::ncRNA
+                | Biotype::miRNA
+                | Biotype::misc_RNA
+                | Biotype::rRNA
+                | Biotype::Mt_rRNA
+                | Biotype::tRNA
+                | Biotype::Mt_tRNA
+                | Biotype::snRNA
+                | Biotype::snoRNA
+                | Biotype::scaRNA
+                | Biotype::scRNA
+                | Biotype::sRNA
+                | Biotype::Y_RNA
+                | Biotype::vault_RNA
+                | Biotype::ribozyme
+                | Biotype::RNase_MRP_RNA
+                | Biotype::RNase_P_RNA
+                | Biotype::telomerase_RNA
+        )
+    }
+}
+
+/// The class of a feature in the Ensembl Regulatory Build, as reported in VEP regulatory consequences.
+/// * Classes this crate doesn't know deserialize as [RegulatoryBiotype::Unknown].
+#[derive(
+    Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[allow(non_camel_case_types)]
+pub enum RegulatoryBiotype {
+    promoter,
+    promoter_flanking_region,
+    enhancer,
+    CTCF_binding_site,
+    TF_binding_site,
+    open_chromatin_region,
+    #[default]
+    #[serde(other)]
     Unknown,
 }
-impl Default for Biotype {
-    fn default() -> Self {
-        Self::Unknown
+
+/// A Sequence Ontology consequence term, as reported in VEP `consequence_terms`.
+/// * Declared from most to least severe, following VEP's ranking, so the derived ordering ranks severity.
+/// * Terms this crate doesn't know deserialize as [Consequence::Unknown].
+/// ```
+/// use rs_embl::Consequence;
+/// let consequence = Consequence::from("stop_gained");
+/// assert_eq!(consequence.so_accession(), Some("SO:0001587"));
+/// assert!(consequence < Consequence::missense_variant);
+/// ```
+#[derive(
+    Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[allow(non_camel_case_types)]
+pub enum Consequence {
+    transcript_ablation,
+    splice_acceptor_variant,
+    splice_donor_variant,
+    stop_gained,
+    frameshift_variant,
+    stop_lost,
+    start_lost,
+    transcript_amplification,
+    feature_elongation,
+    feature_truncation,
+    inframe_insertion,
+    inframe_deletion,
+    missense_variant,
+    protein_altering_variant,
+    splice_donor_5th_base_variant,
+    splice_region_variant,
+    splice_donor_region_variant,
+    splice_polypyrimidine_tract_variant,
+    incomplete_terminal_codon_variant,
+    start_retained_variant,
+    stop_retained_variant,
+    synonymous_variant,
+    coding_sequence_variant,
+    mature_miRNA_variant,
+    #[serde(rename = "5_prime_UTR_variant")]
+    five_prime_UTR_variant,
+    #[serde(rename = "3_prime_UTR_variant")]
+    three_prime_UTR_variant,
+    non_coding_transcript_exon_variant,
+    intron_variant,
+    NMD_transcript_variant,
+    non_coding_transcript_variant,
+    coding_transcript_variant,
+    upstream_gene_variant,
+    downstream_gene_variant,
+    TFBS_ablation,
+    TFBS_amplification,
+    TF_binding_site_variant,
+    regulatory_region_ablation,
+    regulatory_region_amplification,
+    regulatory_region_variant,
+    intergenic_variant,
+    sequence_variant,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+impl Consequence {
+    /// The Sequence Ontology accession of the term, or [None] for [Consequence::Unknown].
+    pub fn so_accession(&self) -> Option<&'static str> {
+        Some(match self {
+            Consequence::transcript_ablation => "SO:0001893",
+            Consequence::splice_acceptor_variant => "SO:0001574",
+            Consequence::splice_donor_variant => "SO:0001575",
+            Consequence::stop_gained => "SO:0001587",
+            Consequence::frameshift_variant => "SO:0001589",
+            Consequence::stop_lost => "SO:0001578",
+            Consequence::start_lost => "SO:0002012",
+            Consequence::transcript_amplification => "SO:0001889",
+            Consequence::feature_elongation => "SO:0001907",
+            Consequence::feature_truncation => "SO:0001906",
+            Consequence::inframe_insertion => "SO:0001821",
+            Consequence::inframe_deletion => "SO:0001822",
+            Consequence::missense_variant => "SO:0001583",
+            Consequence::protein_altering_variant => "SO:0001818",
+            Consequence::splice_donor_5th_base_variant => "SO:0001787",
+            Consequence::splice_region_variant => "SO:0001630",
+            Consequence::splice_donor_region_variant => "SO:0002170",
+            Consequence::splice_polypyrimidine_tract_variant => "SO:0002169",
+            Consequence::incomplete_terminal_codon_variant => "SO:0001626",
+            Consequence::start_retained_variant => "SO:0002019",
+            Consequence::stop_retained_variant => "SO:0001567",
+            Consequence::synonymous_variant => "SO:0001819",
+            Consequence::coding_sequence_variant => "SO:0001580",
+            Consequence::mature_miRNA_variant => "SO:0001620",
+            Consequence::five_prime_UTR_variant => "SO:0001623",
+            Consequence::three_prime_UTR_variant => "SO:0001624",
+            Consequence::non_coding_transcript_exon_variant => "SO:0001792",
+            Consequence::intron_variant => "SO:0001627",
+            Consequence::NMD_transcript_variant => "SO:0001621",
+            Consequence::non_coding_transcript_variant => "SO:0001619",
+            Consequence::coding_transcript_variant => "SO:0001968",
+            Consequence::upstream_gene_variant => "SO:0001631",
+            Consequence::downstream_gene_variant => "SO:0001632",
+            Consequence::TFBS_ablation => "SO:0001895",
+            Consequence::TFBS_amplification => "SO:0001892",
+            Consequence::TF_binding_site_variant => "SO:0001782",
+            Consequence::regulatory_region_ablation => "SO:0001894",
+            Consequence::regulatory_region_amplification => "SO:0001891",
+            Consequence::regulatory_region_variant => "SO:0001566",
+            Consequence::intergenic_variant => "SO:0001628",
+            Consequence::sequence_variant => "SO:0001060",
+            Consequence::Unknown => return None,
+        })
+    }
+
+    /// Whether the term changes the encoded protein: substitutions, in frame indels, frameshifts
+    /// and gained or lost start and stop codons.
+    /// * Not synonymous, retained start or stop, or unspecified coding sequence variants.
+    pub fn is_coding_effect(&self) -> bool {
+        matches!(
+            self,
+            Consequence::stop_gained
+                | Consequence::frameshift_variant
+                | Consequence::stop_lost
+                | Consequence::start_lost
+                | Consequence::inframe_insertion
+                | Consequence::inframe_deletion
+                | Consequence::missense_variant
+                | Consequence::protein_altering_variant
+        )
+    }
+
+    /// Whether the term is in or near a splice site, from the essential donor and acceptor sites
+    /// to the wider splice region.
+    pub fn affects_splicing(&self) -> bool {
+        matches!(
+            self,
+            Consequence::splice_acceptor_variant
+                | Consequence::splice_donor_variant
+                | Consequence::splice_donor_5th_base_variant
+                | Consequence::splice_region_variant
+                | Consequence::splice_donor_region_variant
+                | Consequence::splice_polypyrimidine_tract_variant
+        )
+    }
+}
+/// The most severe of `terms`, by the severity ordering of [Consequence].
+/// * [Consequence::Unknown] ranks least severe, and is returned for no terms.
+/// ```
+/// use rs_embl::{most_severe_consequence, Consequence};
+/// let terms = [Consequence::intron_variant, Consequence::splice_region_variant];
+/// assert_eq!(most_severe_consequence(&terms), Consequence::splice_region_variant);
+/// assert_eq!(most_severe_consequence(&[]), Consequence::Unknown);
+/// ```
+pub fn most_severe_consequence<'a>(
+    terms: impl IntoIterator<Item = &'a Consequence>,
+) -> Consequence {
+    terms.into_iter().min().copied().unwrap_or_default()
+}
+
+impl From<&str> for Consequence {
+    fn from(value: &str) -> Self {
+        Consequence::deserialize(de::value::StrDeserializer::<de::value::Error>::new(value))
+            .unwrap_or_default()
     }
 }
 
-const FIELDS: &'static [&'static str] = &["+/1", "-/-1"];
+const FIELDS: &[&str] = &["+/1", "-/-1"];
 impl<'de> Deserialize<'de> for Strand {
     fn deserialize<D>(deserializer: D) -> Result<Strand, D::Error>
     where
@@ -167,18 +485,16 @@ impl<'de> Deserialize<'de> for Strand {
             where
                 E: de::Error,
             {
-                println!("str{}", value);
                 match value {
                     "+" | "1" => Ok(Strand::PLUS),
                     "-" | "-1" => Ok(Strand::MINUS),
                     _ => Err(de::Error::unknown_field(value, FIELDS)),
                 }
             }
-            fn visit_i32<E>(self, value: i32) -> Result<Strand, E>
+            fn visit_i64<E>(self, value: i64) -> Result<Strand, E>
             where
                 E: de::Error,
             {
-                println!("int{}", value);
                 match value {
                     1 => Ok(Strand::PLUS),
                     -1 => Ok(Strand::MINUS),
@@ -188,8 +504,139 @@ impl<'de> Deserialize<'de> for Strand {
                     )),
                 }
             }
+            fn visit_u64<E>(self, value: u64) -> Result<Strand, E>
+            where
+                E: de::Error,
+            {
+                self.visit_i64(value.try_into().unwrap_or(i64::MAX))
+            }
         }
 
         deserializer.deserialize_any(StrandVisitor)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_round_trip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct AsBool {
+            #[serde(with = "canonical_as_bool")]
+            canonical: Canonical,
+        }
+        for canonical in [Canonical::CANONICAL, Canonical::NONCANONICAL] {
+            let json = serde_json::to_string(&canonical).unwrap();
+            assert_eq!(serde_json::from_str::<Canonical>(&json).unwrap(), canonical);
+            let as_bool = AsBool { canonical };
+            let json = serde_json::to_string(&as_bool).unwrap();
+            assert_eq!(serde_json::from_str::<AsBool>(&json).unwrap(), as_bool);
+        }
+        assert_eq!(serde_json::to_string(&Canonical::CANONICAL).unwrap(), "1");
+        let as_bool = serde_json::to_string(&AsBool {
+            canonical: Canonical::NONCANONICAL,
+        });
+        assert_eq!(as_bool.unwrap(), r#"{"canonical":false}"#);
+    }
+
+    #[test]
+    fn test_regulatory_biotype() {
+        let biotypes: Vec<RegulatoryBiotype> =
+            serde_json::from_str(r#"["CTCF_binding_site", "enhancer", "silencer"]"#).unwrap();
+        assert_eq!(
+            biotypes,
+            [
+                RegulatoryBiotype::CTCF_binding_site,
+                RegulatoryBiotype::enhancer,
+                RegulatoryBiotype::Unknown
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strand_serde() {
+        let strands: Vec<Strand> = serde_json::from_str(r#"[1, -1, "+", "-"]"#).unwrap();
+        assert_eq!(
+            strands,
+            [Strand::PLUS, Strand::MINUS, Strand::PLUS, Strand::MINUS]
+        );
+        assert_eq!(serde_json::to_string(&strands).unwrap(), "[1,-1,1,-1]");
+        assert!(serde_json::from_str::<Strand>("0").is_err());
+        assert!(serde_json::from_str::<Strand>("2").is_err());
+    }
+
+    #[test]
+    fn test_consequence() {
+        let terms: Vec<Consequence> =
+            serde_json::from_str(r#"["5_prime_UTR_variant", "stop_gained", "made_up_variant"]"#)
+                .unwrap();
+        assert_eq!(
+            terms,
+            [
+                Consequence::five_prime_UTR_variant,
+                Consequence::stop_gained,
+                Consequence::Unknown
+            ]
+        );
+        assert_eq!(terms[0].so_accession(), Some("SO:0001623"));
+        assert_eq!(terms[2].so_accession(), None);
+        assert_eq!(
+            serde_json::to_string(&terms[0]).unwrap(),
+            r#""5_prime_UTR_variant""#
+        );
+        assert_eq!(terms.iter().min(), Some(&Consequence::stop_gained));
+    }
+
+    #[test]
+    fn test_consequence_groups() {
+        let all: Vec<Consequence> = serde_json::from_str(
+            r#"["transcript_ablation","splice_acceptor_variant","splice_donor_variant","stop_gained","frameshift_variant","stop_lost","start_lost","transcript_amplification","feature_elongation","feature_truncation","inframe_insertion","inframe_deletion","missense_variant","protein_altering_variant","splice_donor_5th_base_variant","splice_region_variant","splice_donor_region_variant","splice_polypyrimidine_tract_variant","incomplete_terminal_codon_variant","start_retained_variant","stop_retained_variant","synonymous_variant","coding_sequence_variant","mature_miRNA_variant","5_prime_UTR_variant","3_prime_UTR_variant","non_coding_transcript_exon_variant","intron_variant","NMD_transcript_variant","non_coding_transcript_variant","coding_transcript_variant","upstream_gene_variant","downstream_gene_variant","TFBS_ablation","TFBS_amplification","TF_binding_site_variant","regulatory_region_ablation","regulatory_region_amplification","regulatory_region_variant","intergenic_variant","sequence_variant","made_up_variant"]"#,
+        )
+        .unwrap();
+        let coding: Vec<_> = all.iter().filter(|c| c.is_coding_effect()).collect();
+        assert_eq!(
+            coding,
+            [
+                &Consequence::stop_gained,
+                &Consequence::frameshift_variant,
+                &Consequence::stop_lost,
+                &Consequence::start_lost,
+                &Consequence::inframe_insertion,
+                &Consequence::inframe_deletion,
+                &Consequence::missense_variant,
+                &Consequence::protein_altering_variant,
+            ]
+        );
+        let splicing: Vec<_> = all.iter().filter(|c| c.affects_splicing()).collect();
+        assert_eq!(
+            splicing,
+            [
+                &Consequence::splice_acceptor_variant,
+                &Consequence::splice_donor_variant,
+                &Consequence::splice_donor_5th_base_variant,
+                &Consequence::splice_region_variant,
+                &Consequence::splice_donor_region_variant,
+                &Consequence::splice_polypyrimidine_tract_variant,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_biotype_groups() {
+        assert!(Biotype::protein_coding.is_coding());
+        assert!(Biotype::IG_V_gene.is_coding());
+        assert!(Biotype::TR_C_gene.is_coding());
+        assert!(!Biotype::protein_coding_CDS_not_defined.is_coding());
+        assert!(Biotype::processed_pseudogene.is_pseudogene());
+        assert!(Biotype::IG_V_pseudogene.is_pseudogene());
+        assert!(!Biotype::IG_V_pseudogene.is_coding());
+        assert!(Biotype::lncRNA.is_noncoding_rna());
+        assert!(Biotype::Mt_tRNA.is_noncoding_rna());
+        assert!(!Biotype::rRNA_pseudogene.is_noncoding_rna());
+        assert!(!Biotype::Unknown.is_coding());
+        assert!(!Biotype::Unknown.is_pseudogene());
+        assert!(!Biotype::Unknown.is_noncoding_rna());
+    }
+}
@@ -0,0 +1,134 @@
+//! Genes looked up by their stable identifier, with their transcripts.
+use serde::{Deserialize, Serialize};
+
+use crate::{transcript::Transcript, Client, EnsemblError};
+
+/// A gene looked up by its stable identifier, eg `"ENSG00000118271"`.
+/// ```no_run
+/// # tokio::runtime::Builder::new_current_thread()
+/// #       .enable_all()
+/// #       .build()
+/// #       .unwrap()
+/// #       .block_on(async {
+/// use rs_embl::{gene::Gene, Getter};
+///
+/// let genes = Getter::<Gene>::new();
+/// let transcripts = Getter::new();
+/// let gene = genes.client().get("ENSG00000118271".to_owned()).await.unwrap();
+/// for transcript in gene.transcripts(transcripts.client()).await {
+///     let transcript = transcript.unwrap();
+///     println!("{} {:?}", transcript.id, transcript.biotype);
+/// }
+/// # });
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct Gene {
+    pub id: String,
+    #[serde(default)]
+    pub display_name: String,
+    pub description: Option<String>,
+    pub start: u32,
+    pub end: u32,
+    pub strand: crate::Strand,
+    #[serde(default)]
+    pub seq_region_name: String,
+    pub species: String,
+    #[serde(default)]
+    pub biotype: crate::Biotype,
+    /// The gene's transcripts, with their exons, translations and UTRs, as returned by the lookup.
+    #[serde(rename = "Transcript", default)]
+    pub child_transcripts: Vec<Transcript>,
+}
+impl Gene {
+    /// The stable identifiers of the gene's transcripts.
+    pub fn transcript_ids(&self) -> impl Iterator<Item = &str> {
+        self.child_transcripts
+            .iter()
+            .map(|transcript| transcript.id.as_str())
+    }
+
+    /// The canonical transcript of the gene, the usual choice for a gene level report.
+    pub fn canonical_transcript(&self) -> Option<&Transcript> {
+        self.child_transcripts
+            .iter()
+            .find(|transcript| transcript.canonical == crate::Canonical::CANONICAL)
+    }
+
+    /// Fetch every transcript of the gene through `client`, in the order the lookup listed them.
+    /// * Useful to refresh the [Gene::child_transcripts], or for a gene deserialized without them.
+    /// * The requests are queued together, so they share the [crate::Getter]'s batches.
+    /// * A transcript that fails to load is an [EnsemblError] in its place, the others are still returned.
+    /// # Panics
+    ///
+    /// As [Client::get].
+    pub async fn transcripts(
+        &self,
+        client: Client<'static, Transcript>,
+    ) -> Vec<Result<Transcript, EnsemblError>> {
+        client
+            .get_many(self.transcript_ids().map(str::to_owned))
+            .await
+    }
+}
+
+impl crate::EnsemblPostEndpoint for Gene {
+    fn extension() -> &'static str {
+        "/lookup/id"
+    }
+    fn payload_template() -> &'static str {
+        r#"{"expand": 1, "utr" : 1, "ids" : {ids}}"#
+    }
+    fn input(&self) -> &str {
+        &self.id
+    }
+
+    fn max_post_size() -> usize {
+        1000
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::{FixtureTransport, Getter};
+
+    const TTR_GENE_JSON: &str = r#"{"id":"ENSG00000118271","display_name":"TTR","description":"transthyretin [Source:HGNC Symbol;Acc:HGNC:12405]","start":31591766,"end":31599023,"strand":1,"seq_region_name":"18","species":"homo_sapiens","biotype":"protein_coding","object_type":"Gene","Transcript":[{"id":"ENST00000649620","display_name":"TTR-204","biotype":"protein_coding","is_canonical":0,"start":31591792,"end":31598821,"strand":1,"species":"homo_sapiens"},{"id":"ENST00000237014","display_name":"TTR-201","biotype":"protein_coding","is_canonical":1,"start":31591766,"end":31599023,"strand":1,"species":"homo_sapiens"}]}"#;
+
+    #[tokio::test]
+    async fn test_gene_transcripts() {
+        let gene: Gene = serde_json::from_str(TTR_GENE_JSON).unwrap();
+        assert_eq!(gene.display_name, "TTR");
+        assert_eq!(
+            gene.transcript_ids().collect::<Vec<_>>(),
+            ["ENST00000649620", "ENST00000237014"]
+        );
+        assert_eq!(gene.child_transcripts[0].display_name, "TTR-204");
+        let json: serde_json::Value = serde_json::from_str(TTR_GENE_JSON).unwrap();
+        let getter = Getter::<Transcript>::builder()
+            .transport(FixtureTransport::new(200, &json["Transcript"].to_string()))
+            .build();
+        let transcripts = gene.transcripts(getter.client()).await;
+        assert_eq!(transcripts.len(), 2);
+        assert_eq!(transcripts[0].as_ref().unwrap().id, "ENST00000649620");
+        assert_eq!(transcripts[1].as_ref().unwrap().display_name, "TTR-201");
+
+        // A transcript Ensembl doesn't return is an error in its place, not a panic.
+        let json = format!("[{}]", json["Transcript"][1]);
+        let getter = Getter::<Transcript>::builder()
+            .transport(FixtureTransport::new(200, &json))
+            .build();
+        let transcripts = gene.transcripts(getter.client()).await;
+        assert!(transcripts[0].is_err());
+        assert_eq!(transcripts[1].as_ref().unwrap().id, "ENST00000237014");
+    }
+
+    #[test]
+    fn test_canonical_transcript() {
+        let mut gene: Gene = serde_json::from_str(TTR_GENE_JSON).unwrap();
+        let json = serde_json::to_string(&gene).unwrap();
+        assert_eq!(serde_json::from_str::<Gene>(&json).unwrap(), gene);
+        assert_eq!(gene.canonical_transcript().unwrap().id, "ENST00000237014");
+        gene.child_transcripts.remove(1);
+        assert_eq!(gene.canonical_transcript(), None);
+    }
+}
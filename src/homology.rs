@@ -0,0 +1,124 @@
+//! Orthologues and paralogues of a gene, from the `/homology/id` endpoint.
+//! ```no_run
+//! # tokio::runtime::Builder::new_current_thread()
+//! #       .enable_all()
+//! #       .build()
+//! #       .unwrap()
+//! #       .block_on(async {
+//! use rs_embl::homology::homologies;
+//!
+//! let client = reqwest::Client::new();
+//! let mouse = homologies(&client, "homo_sapiens", "ENSG00000118271", Some("mus_musculus"))
+//!     .await
+//!     .unwrap();
+//! for homology in mouse.iter().filter(|h| h.is_ortholog()) {
+//!     println!("{} {:.1}%", homology.target.id, homology.target.perc_id);
+//! }
+//! # });
+//! ```
+use serde::{Deserialize, Serialize};
+
+use crate::{get_json, EnsemblError, Transport, ENSEMBL_SERVER};
+
+/// A gene related to the requested gene by descent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Homology {
+    /// eg "ortholog_one2one" or "within_species_paralog".
+    #[serde(rename = "type")]
+    pub homology_type: String,
+    pub target: HomologyMember,
+    /// The taxonomic level of the last common ancestor, eg "Euarchontoglires".
+    pub taxonomy_level: Option<String>,
+}
+impl Homology {
+    pub fn is_ortholog(&self) -> bool {
+        self.homology_type.starts_with("ortholog")
+    }
+
+    pub fn is_paralog(&self) -> bool {
+        self.homology_type.ends_with("paralog")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HomologyMember {
+    /// The gene ID.
+    pub id: String,
+    pub species: String,
+    pub protein_id: Option<String>,
+    /// The percentage of this member's protein identical to the requested gene's.
+    #[serde(default)]
+    pub perc_id: f64,
+    /// The percentage of this member's protein with a positive substitution score.
+    #[serde(default)]
+    pub perc_pos: f64,
+}
+
+#[derive(Deserialize)]
+struct HomologyResponse {
+    data: Vec<HomologyData>,
+}
+#[derive(Deserialize)]
+struct HomologyData {
+    homologies: Vec<Homology>,
+}
+
+/// Get the homologies of the gene `id` in `species`, limited to `target_species` if given.
+/// # Errors
+///
+/// Returns an [EnsemblError] for `id` if the request fails or Ensembl reports an error.
+pub async fn homologies(
+    transport: &dyn Transport,
+    species: &str,
+    id: &str,
+    target_species: Option<&str>,
+) -> Result<Vec<Homology>, EnsemblError> {
+    let mut url = format!("{ENSEMBL_SERVER}/homology/id/{species}/{id}");
+    if let Some(target_species) = target_species {
+        url.push_str(&format!("?target_species={target_species}"));
+    }
+    let response: HomologyResponse = get_json(transport, &url, id).await?;
+    Ok(response
+        .data
+        .into_iter()
+        .flat_map(|data| data.homologies)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GetFixture;
+
+    #[tokio::test]
+    async fn test_homologies() {
+        let transport = GetFixture::new(
+            r#"{"data":[{"id":"ENSG00000118271","homologies":[
+                {"type":"ortholog_one2one","method_link_type":"ENSEMBL_ORTHOLOGUES","taxonomy_level":"Euarchontoglires","source":{"id":"ENSG00000118271","species":"homo_sapiens","perc_id":80.2721},"target":{"id":"ENSMUSG00000061808","species":"mus_musculus","protein_id":"ENSMUSP00000075213","perc_id":80.2721,"perc_pos":89.1156}},
+                {"type":"within_species_paralog","taxonomy_level":"Bilateria","target":{"id":"ENSG00000000001","species":"homo_sapiens","perc_id":20.5}}
+            ]}]}"#,
+        );
+        let homologies = homologies(
+            &transport,
+            "homo_sapiens",
+            "ENSG00000118271",
+            Some("mus_musculus"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            transport.urls()[0],
+            "https://rest.ensembl.org/homology/id/homo_sapiens/ENSG00000118271?target_species=mus_musculus"
+        );
+        assert_eq!(homologies.len(), 2);
+        assert!(homologies[0].is_ortholog());
+        assert_eq!(homologies[0].target.id, "ENSMUSG00000061808");
+        assert!(homologies[1].is_paralog());
+        assert_eq!(homologies[1].target.perc_pos, 0.0);
+        let json = serde_json::to_string(&homologies).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<Homology>>(&json).unwrap(),
+            homologies
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Server metadata from the `/info` endpoints, to check the assembly and species before querying.
+//! ```no_run
+//! # tokio::runtime::Builder::new_current_thread()
+//! #       .enable_all()
+//! #       .build()
+//! #       .unwrap()
+//! #       .block_on(async {
+//! use rs_embl::info::assembly;
+//!
+//! let client = reqwest::Client::new();
+//! let info = assembly(&client, "homo_sapiens").await.unwrap();
+//! assert_eq!(info.default_coord_system_version, "GRCh38");
+//! # });
+//! ```
+use serde::{Deserialize, Serialize};
+
+use crate::{get_json, EnsemblError, Transport, ENSEMBL_SERVER};
+
+/// The assembly a species is served on.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct AssemblyInfo {
+    /// The full assembly name including its patch, eg "GRCh38.p14".
+    pub assembly_name: String,
+    /// The assembly coordinates are reported in, eg "GRCh38".
+    pub default_coord_system_version: String,
+    pub assembly_accession: Option<String>,
+    pub assembly_date: Option<String>,
+    /// The names of the chromosomes.
+    #[serde(default)]
+    pub karyotype: Vec<String>,
+}
+
+/// A species available on the server.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct SpeciesInfo {
+    /// The production name used in endpoint paths, eg "homo_sapiens".
+    pub name: String,
+    pub display_name: Option<String>,
+    pub common_name: Option<String>,
+    /// The default assembly of the species, eg "GRCh38".
+    pub assembly: Option<String>,
+    pub division: Option<String>,
+    pub taxon_id: Option<String>,
+    pub release: Option<u32>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SpeciesList {
+    species: Vec<SpeciesInfo>,
+}
+
+/// Get the assembly `species` is served on.
+/// # Errors
+///
+/// Returns an [EnsemblError] for `species` if the request fails or the species isn't known.
+pub async fn assembly(
+    transport: &dyn Transport,
+    species: &str,
+) -> Result<AssemblyInfo, EnsemblError> {
+    let url = format!("{ENSEMBL_SERVER}/info/assembly/{species}");
+    get_json(transport, &url, species).await
+}
+
+/// List the species available on the server.
+/// # Errors
+///
+/// Returns an [EnsemblError] with an input of `"species"` if the request fails.
+pub async fn species(transport: &dyn Transport) -> Result<Vec<SpeciesInfo>, EnsemblError> {
+    let url = format!("{ENSEMBL_SERVER}/info/species");
+    let list: SpeciesList = get_json(transport, &url, "species").await?;
+    Ok(list.species)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GetFixture;
+
+    #[tokio::test]
+    async fn test_info() {
+        let transport = GetFixture::new(
+            r#"{"assembly_name":"GRCh38.p14","default_coord_system_version":"GRCh38","assembly_accession":"GCA_000001405.29","assembly_date":"2013-12","karyotype":["1","2","X","Y","MT"],"top_level_region":[{"coord_system":"chromosome","name":"1","length":248956422}]}"#,
+        );
+        let info = assembly(&transport, "homo_sapiens").await.unwrap();
+        assert_eq!(info.default_coord_system_version, "GRCh38");
+        assert_eq!(info.karyotype.len(), 5);
+        let json = serde_json::to_string(&info).unwrap();
+        assert_eq!(serde_json::from_str::<AssemblyInfo>(&json).unwrap(), info);
+
+        let transport = GetFixture::new(
+            r#"{"species":[{"name":"homo_sapiens","display_name":"Human","common_name":"human","assembly":"GRCh38","accession":"GCA_000001405.29","division":"EnsemblVertebrates","taxon_id":"9606","release":110,"aliases":["human","hsapiens"],"strain":null,"groups":["core","variation"]}]}"#,
+        );
+        let species = species(&transport).await.unwrap();
+        assert_eq!(species[0].name, "homo_sapiens");
+        assert_eq!(species[0].assembly.as_deref(), Some("GRCh38"));
+    }
+}
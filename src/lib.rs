@@ -1,13 +1,17 @@
 //! A tool for interacting with the POST endpoints of Ensembl REST API.
 //!  * Spawns an async task that repeatedly polls requests made to its [Client] objects.
 //!  * Bundles those requests and posts them to the Ensembl endpoints, asyncronously returning [serde::Deserialize] objects representing the result.
+//!  * Enable the `gzip` feature to receive compressed responses, which are much smaller for large VEP batches.
+//!  * Enable the `tracing` feature to log failed posts and unparseable responses through [tracing](https://docs.rs/tracing).
+//!    Nothing is logged without it.
+//!  * Enable the `rayon` feature to translate many sequences in parallel with [transcript::translate_many].
 //! ```
 //! # tokio::runtime::Builder::new_current_thread()
 //! #       .enable_all()
 //! #       .build()
 //! #       .unwrap()
 //! #       .block_on(async {
-//! use rs_embl::{Getter, vep::VEPAnalysis};
+//! use rs_embl::{EnsemblError, Getter, vep::VEPAnalysis};
 //!
 //! //Wrap in an async tokio runtime
 //!
@@ -20,7 +24,7 @@
 //!     })
 //!     .collect();
 //! for h in handles.into_iter() {
-//!     let vep: Option<VEPAnalysis> = h.await.unwrap();
+//!     let vep: Result<VEPAnalysis, EnsemblError> = h.await.unwrap();
 //!     println!("{:#?}", vep.unwrap());
 //! }
 //! # });
@@ -28,7 +32,21 @@
 mod api;
 pub use api::*;
 mod descriptors;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod gene;
+pub mod homology;
+pub mod info;
+mod logging;
+pub mod mapping;
+pub mod overlap;
+pub mod phenotype;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod pipeline;
+pub mod regulatory;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod replay;
 pub mod sequence;
 pub mod transcript;
+pub mod variation;
 pub mod vep;
 pub use descriptors::*;
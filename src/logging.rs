@@ -0,0 +1,28 @@
+//! Logging macros that forward to `tracing` when the `tracing` feature is enabled, and compile to
+//! nothing otherwise.
+//! * Only plain format strings are supported, so the arguments type check either way.
+
+macro_rules! log_event {
+    ($level:ident, $($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)*);
+        #[cfg(not(feature = "tracing"))]
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    }};
+}
+
+macro_rules! log_debug {
+    ($($arg:tt)*) => { $crate::logging::log_event!(debug, $($arg)*) };
+}
+
+macro_rules! log_warn {
+    ($($arg:tt)*) => { $crate::logging::log_event!(warn, $($arg)*) };
+}
+
+macro_rules! log_error {
+    ($($arg:tt)*) => { $crate::logging::log_event!(error, $($arg)*) };
+}
+
+pub(crate) use {log_debug, log_error, log_event, log_warn};
@@ -1,4 +1,4 @@
-use rs_embl::{sequence::CdnaSequence, vep::VEPAnalysis, Getter};
+use rs_embl::{sequence::CdnaSequence, vep::VEPAnalysis, EnsemblError, Getter};
 #[cfg_attr(not(target_arch = "wasm32"), tokio::main)]
 #[cfg_attr(target_arch = "wasm32", tokio::main(flavor = "current_thread"))]
 async fn main() {
@@ -10,7 +10,6 @@ async fn main() {
             tokio::spawn(async move { v.get(id.to_string()).await })
         })
         .collect();
-    drop(v);
     let v2 = Getter::<CdnaSequence>::new();
     let handles2: Vec<_> = ["ENST00000237014", "ENSE00003556666"]
         .iter()
@@ -19,9 +18,8 @@ async fn main() {
             tokio::spawn(async move { v.get(id.to_string()).await })
         })
         .collect();
-    drop(v2);
     for h in handles.into_iter() {
-        let vep: Option<VEPAnalysis> = h.await.unwrap();
+        let vep: Result<VEPAnalysis, EnsemblError> = h.await.unwrap();
         println!("{:#?}", vep.unwrap());
     }
     for h in handles2.into_iter() {
@@ -0,0 +1,131 @@
+//! Genomic coordinates of transcript relative positions, from the `/map/cdna` and `/map/cds` endpoints.
+//! ```no_run
+//! # tokio::runtime::Builder::new_current_thread()
+//! #       .enable_all()
+//! #       .build()
+//! #       .unwrap()
+//! #       .block_on(async {
+//! use rs_embl::mapping::{map_to_genomic, TranscriptCoordinates};
+//!
+//! let client = reqwest::Client::new();
+//! let map = map_to_genomic(&client, TranscriptCoordinates::Cds, "ENST00000237014", 148, 148)
+//!     .await
+//!     .unwrap();
+//! for block in map.blocks() {
+//!     println!("{}:{}-{}", block.seq_region_name, block.start, block.end);
+//! }
+//! # });
+//! ```
+use serde::{Deserialize, Serialize};
+
+use crate::{get_json, EnsemblError, Transport, ENSEMBL_SERVER};
+
+/// The transcript relative coordinates being mapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TranscriptCoordinates {
+    /// Positions along the spliced transcript, as in [crate::vep::TranscriptConsequence::cdna_start].
+    Cdna,
+    /// Positions along the coding sequence, from the first base of the start codon.
+    Cds,
+}
+impl TranscriptCoordinates {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TranscriptCoordinates::Cdna => "cdna",
+            TranscriptCoordinates::Cds => "cds",
+        }
+    }
+}
+
+/// The genomic blocks a transcript relative range maps to.
+/// * A range spanning an exon junction maps to one block per exon.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct CoordinateMap {
+    pub mappings: Vec<Mapping>,
+}
+impl CoordinateMap {
+    /// The mapped blocks, skipping any gaps where part of the range has no genomic equivalent.
+    pub fn blocks(&self) -> impl Iterator<Item = &Mapping> {
+        self.mappings.iter().filter(|mapping| !mapping.is_gap())
+    }
+
+    /// Whether the range maps to more than one block, or only partly maps.
+    pub fn is_gapped(&self) -> bool {
+        self.mappings.len() > 1
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct Mapping {
+    pub start: u32,
+    pub end: u32,
+    pub strand: i8,
+    #[serde(default)]
+    pub seq_region_name: String,
+    /// eg "chromosome".
+    #[serde(default)]
+    pub coord_system: String,
+    pub assembly_name: Option<String>,
+    pub rank: Option<u32>,
+    /// 1 for a gap in the mapping, 0 otherwise.
+    #[serde(default)]
+    pub gap: u8,
+}
+impl Mapping {
+    pub fn is_gap(&self) -> bool {
+        self.gap != 0
+    }
+}
+
+/// Map `start..=end` on transcript `id` to genomic coordinates.
+/// # Errors
+///
+/// Returns an [EnsemblError] for `id` if the request fails or Ensembl reports an error, eg for a
+/// range outside the transcript.
+pub async fn map_to_genomic(
+    transport: &dyn Transport,
+    coordinates: TranscriptCoordinates,
+    id: &str,
+    start: u32,
+    end: u32,
+) -> Result<CoordinateMap, EnsemblError> {
+    let url = format!(
+        "{ENSEMBL_SERVER}/map/{}/{id}/{start}..{end}",
+        coordinates.as_str()
+    );
+    get_json(transport, &url, id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GetFixture;
+
+    #[tokio::test]
+    async fn test_gapped_mapping() {
+        let transport = GetFixture::new(
+            r#"{"mappings":[
+                {"assembly_name":"GRCh38","coord_system":"chromosome","end":31591971,"gap":0,"rank":0,"seq_region_name":"18","start":31591970,"strand":1},
+                {"assembly_name":"GRCh38","coord_system":"chromosome","end":31592896,"gap":0,"rank":0,"seq_region_name":"18","start":31592896,"strand":1}
+            ]}"#,
+        );
+        let map = map_to_genomic(
+            &transport,
+            TranscriptCoordinates::Cds,
+            "ENST00000237014",
+            68,
+            70,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            transport.urls()[0],
+            "https://rest.ensembl.org/map/cds/ENST00000237014/68..70"
+        );
+        assert!(map.is_gapped());
+        let blocks: Vec<_> = map.blocks().map(|b| (b.start, b.end)).collect();
+        assert_eq!(blocks, [(31591970, 31591971), (31592896, 31592896)]);
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(serde_json::from_str::<CoordinateMap>(&json).unwrap(), map);
+    }
+}
@@ -0,0 +1,217 @@
+//! Features overlapping a genomic region, from the `/overlap/region` endpoint.
+//! ```no_run
+//! # tokio::runtime::Builder::new_current_thread()
+//! #       .enable_all()
+//! #       .build()
+//! #       .unwrap()
+//! #       .block_on(async {
+//! use rs_embl::overlap::{overlap_region, OverlapFeatureType};
+//!
+//! let client = reqwest::Client::new();
+//! let features = overlap_region(&client, "18:31591766-31599023", &[OverlapFeatureType::Gene])
+//!     .await
+//!     .unwrap();
+//! for feature in features {
+//!     println!("{} {:?}", feature.id(), feature.biotype());
+//! }
+//! # });
+//! ```
+use serde::{Deserialize, Serialize};
+
+use crate::{get_json, EnsemblError, Transport, ENSEMBL_SERVER};
+
+/// The kinds of feature that can be requested from the overlap endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverlapFeatureType {
+    Gene,
+    Transcript,
+    Exon,
+    Regulatory,
+}
+impl OverlapFeatureType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OverlapFeatureType::Gene => "gene",
+            OverlapFeatureType::Transcript => "transcript",
+            OverlapFeatureType::Exon => "exon",
+            OverlapFeatureType::Regulatory => "regulatory",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct OverlapGene {
+    pub id: String,
+    pub seq_region_name: String,
+    pub start: u32,
+    pub end: u32,
+    pub strand: i8,
+    pub biotype: Option<String>,
+    pub external_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct OverlapTranscript {
+    pub id: String,
+    pub seq_region_name: String,
+    pub start: u32,
+    pub end: u32,
+    pub strand: i8,
+    pub biotype: Option<String>,
+    /// The gene the transcript belongs to.
+    #[serde(rename = "Parent")]
+    pub parent: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct OverlapExon {
+    pub id: String,
+    pub seq_region_name: String,
+    pub start: u32,
+    pub end: u32,
+    pub strand: i8,
+    /// The transcript the exon belongs to.
+    #[serde(rename = "Parent")]
+    pub parent: String,
+    pub rank: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct OverlapRegulatory {
+    pub id: String,
+    pub seq_region_name: String,
+    pub start: u32,
+    pub end: u32,
+    pub strand: i8,
+    /// eg "Enhancer" or "CTCF binding site".
+    pub description: Option<String>,
+}
+
+/// A feature overlapping the requested region, distinguished by its `feature_type`.
+/// * Feature types without a dedicated variant deserialize as [OverlapFeature::Other].
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(tag = "feature_type", rename_all = "snake_case")]
+pub enum OverlapFeature {
+    Gene(OverlapGene),
+    Transcript(OverlapTranscript),
+    Exon(OverlapExon),
+    Regulatory(OverlapRegulatory),
+    #[serde(other)]
+    Other,
+}
+impl OverlapFeature {
+    pub fn id(&self) -> &str {
+        match self {
+            OverlapFeature::Gene(f) => &f.id,
+            OverlapFeature::Transcript(f) => &f.id,
+            OverlapFeature::Exon(f) => &f.id,
+            OverlapFeature::Regulatory(f) => &f.id,
+            OverlapFeature::Other => "",
+        }
+    }
+
+    /// The start and end of the feature, or [None] for [OverlapFeature::Other].
+    pub fn span(&self) -> Option<(u32, u32)> {
+        match self {
+            OverlapFeature::Gene(f) => Some((f.start, f.end)),
+            OverlapFeature::Transcript(f) => Some((f.start, f.end)),
+            OverlapFeature::Exon(f) => Some((f.start, f.end)),
+            OverlapFeature::Regulatory(f) => Some((f.start, f.end)),
+            OverlapFeature::Other => None,
+        }
+    }
+
+    pub fn strand(&self) -> Option<i8> {
+        match self {
+            OverlapFeature::Gene(f) => Some(f.strand),
+            OverlapFeature::Transcript(f) => Some(f.strand),
+            OverlapFeature::Exon(f) => Some(f.strand),
+            OverlapFeature::Regulatory(f) => Some(f.strand),
+            OverlapFeature::Other => None,
+        }
+    }
+
+    /// The biotype of genes and transcripts.
+    pub fn biotype(&self) -> Option<&str> {
+        match self {
+            OverlapFeature::Gene(f) => f.biotype.as_deref(),
+            OverlapFeature::Transcript(f) => f.biotype.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Get the human features of the given types overlapping `region`, eg `"18:31591766-31599023"`.
+/// # Errors
+///
+/// Returns an [EnsemblError] for `region` if the request fails or Ensembl reports an error.
+pub async fn overlap_region(
+    transport: &dyn Transport,
+    region: &str,
+    features: &[OverlapFeatureType],
+) -> Result<Vec<OverlapFeature>, EnsemblError> {
+    let query = features
+        .iter()
+        .map(|feature| format!("feature={}", feature.as_str()))
+        .collect::<Vec<_>>()
+        .join(";");
+    let url = format!("{ENSEMBL_SERVER}/overlap/region/human/{region}?{query}");
+    get_json(transport, &url, region).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GetFixture;
+
+    const TTR_OVERLAP_JSON: &str = r#"[
+        {"feature_type":"gene","id":"ENSG00000118271","seq_region_name":"18","start":31591766,"end":31599023,"strand":1,"biotype":"protein_coding","external_name":"TTR","version":11,"assembly_name":"GRCh38"},
+        {"feature_type":"transcript","id":"ENST00000237014","seq_region_name":"18","start":31591766,"end":31599023,"strand":1,"biotype":"protein_coding","Parent":"ENSG00000118271","tsl":1},
+        {"feature_type":"exon","id":"ENSE00003556666","seq_region_name":"18","start":31591766,"end":31591971,"strand":1,"Parent":"ENST00000237014","rank":1,"exon_id":"ENSE00003556666"},
+        {"feature_type":"regulatory","id":"ENSR00000105783","seq_region_name":"18","start":31591001,"end":31592600,"strand":0,"description":"Promoter"},
+        {"feature_type":"variation","id":"rs28933979","seq_region_name":"18","start":31592974,"end":31592974,"strand":1}
+    ]"#;
+
+    #[tokio::test]
+    async fn test_overlap_region() {
+        let transport = GetFixture::new(TTR_OVERLAP_JSON);
+        let features = overlap_region(
+            &transport,
+            "18:31591766-31599023",
+            &[OverlapFeatureType::Gene, OverlapFeatureType::Regulatory],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            transport.urls()[0],
+            "https://rest.ensembl.org/overlap/region/human/18:31591766-31599023?feature=gene;feature=regulatory"
+        );
+        assert_eq!(features.len(), 5);
+        assert_eq!(features[0].biotype(), Some("protein_coding"));
+        let OverlapFeature::Transcript(transcript) = &features[1] else {
+            panic!("Expected a transcript, got {:?}", features[1]);
+        };
+        assert_eq!(transcript.parent, "ENSG00000118271");
+        assert_eq!(features[2].span(), Some((31591766, 31591971)));
+        assert_eq!(features[3].strand(), Some(0));
+        assert_eq!(features[4], OverlapFeature::Other);
+        let json = serde_json::to_string(&features).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<OverlapFeature>>(&json).unwrap(),
+            features
+        );
+    }
+
+    #[tokio::test]
+    async fn test_overlap_region_error() {
+        let transport = GetFixture::with_status(
+            400,
+            r#"{"error":"18:1-10000000 is larger than the maximum allowed"}"#,
+        );
+        let err = overlap_region(&transport, "18:1-10000000", &[OverlapFeatureType::Gene])
+            .await
+            .unwrap_err();
+        assert_eq!(err.input, "18:1-10000000");
+        assert_eq!(err.status_code, 400);
+    }
+}
@@ -0,0 +1,107 @@
+//! Phenotypes associated with a gene, from the `/phenotype/gene` endpoint.
+//! ```no_run
+//! # tokio::runtime::Builder::new_current_thread()
+//! #       .enable_all()
+//! #       .build()
+//! #       .unwrap()
+//! #       .block_on(async {
+//! use rs_embl::phenotype::phenotypes_by_gene;
+//!
+//! let client = reqwest::Client::new();
+//! for phenotype in phenotypes_by_gene(&client, "homo_sapiens", "TTR").await.unwrap() {
+//!     println!("{}: {}", phenotype.source, phenotype.description);
+//! }
+//! # });
+//! ```
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{get_json, EnsemblError, Transport, ENSEMBL_SERVER};
+
+/// A phenotype associated with a gene, or with a variant in it.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct Phenotype {
+    /// The database reporting the association, eg "ClinVar" or "Orphanet".
+    pub source: String,
+    #[serde(alias = "phenotype_description")]
+    pub description: String,
+    /// The gene the phenotype is associated with.
+    #[serde(default)]
+    pub id: String,
+    pub location: Option<String>,
+    /// The variant the association was reported for, if any.
+    #[serde(rename = "Variation")]
+    pub variation: Option<String>,
+    /// Source specific details, eg `external_id` or `clinvar_clin_sig`, with numbers as strings.
+    #[serde(default, deserialize_with = "stringify_attributes")]
+    pub attributes: BTreeMap<String, String>,
+}
+
+fn stringify_attributes<'de, D>(deserializer: D) -> Result<BTreeMap<String, String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    Ok(values
+        .into_iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(value) => (key, value),
+            value => (key, value.to_string()),
+        })
+        .collect())
+}
+
+/// The IDs of the variants the `phenotypes` were reported for, without duplicates.
+pub fn associated_variations(phenotypes: &[Phenotype]) -> Vec<&str> {
+    let mut variations: Vec<&str> = phenotypes
+        .iter()
+        .filter_map(|phenotype| phenotype.variation.as_deref())
+        .collect();
+    variations.sort_unstable();
+    variations.dedup();
+    variations
+}
+
+/// Get the phenotypes associated with `gene`, a gene symbol or Ensembl ID, including those
+/// reported for variants in the gene.
+/// # Errors
+///
+/// Returns an [EnsemblError] for `gene` if the request fails or Ensembl reports an error.
+pub async fn phenotypes_by_gene(
+    transport: &dyn Transport,
+    species: &str,
+    gene: &str,
+) -> Result<Vec<Phenotype>, EnsemblError> {
+    let url = format!("{ENSEMBL_SERVER}/phenotype/gene/{species}/{gene}?include_associated=1");
+    get_json(transport, &url, gene).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GetFixture;
+
+    #[tokio::test]
+    async fn test_phenotypes_by_gene() {
+        let transport = GetFixture::new(
+            r#"[
+                {"source":"Orphanet","description":"Hereditary ATTR amyloidosis","id":"ENSG00000118271","location":"18:31591766-31599023","attributes":{"external_id":"85447"}},
+                {"source":"ClinVar","description":"Amyloidogenic transthyretin amyloidosis","id":"ENSG00000118271","Variation":"rs28933979","attributes":{"clinvar_clin_sig":"pathogenic","review_status":"criteria provided, multiple submitters, no conflicts","MIM":105210}},
+                {"source":"ClinVar","description":"Cardiomyopathy","id":"ENSG00000118271","Variation":"rs28933979","attributes":{}}
+            ]"#,
+        );
+        let phenotypes = phenotypes_by_gene(&transport, "homo_sapiens", "TTR")
+            .await
+            .unwrap();
+        assert_eq!(phenotypes.len(), 3);
+        assert_eq!(phenotypes[0].attributes["external_id"], "85447");
+        assert_eq!(phenotypes[1].attributes["MIM"], "105210");
+        assert_eq!(associated_variations(&phenotypes), ["rs28933979"]);
+        let json = serde_json::to_string(&phenotypes).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<Phenotype>>(&json).unwrap(),
+            phenotypes
+        );
+    }
+}
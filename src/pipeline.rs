@@ -0,0 +1,184 @@
+//! Chains the VEP, transcript and sequence getters to annotate a variant end to end.
+//! ```no_run
+//! # tokio::runtime::Builder::new_current_thread()
+//! #       .enable_all()
+//! #       .build()
+//! #       .unwrap()
+//! #       .block_on(async {
+//! use rs_embl::{pipeline::annotate_variant, Getter};
+//!
+//! let vep = Getter::new();
+//! let transcripts = Getter::new();
+//! let sequences = Getter::new();
+//! let annotated = annotate_variant(
+//!     "18:g.31592974G>A",
+//!     vep.client(),
+//!     transcripts.client(),
+//!     sequences.client(),
+//! )
+//! .await
+//! .unwrap();
+//! for transcript in annotated.transcripts {
+//!     println!("{}: {:?}", transcript.transcript.id, transcript.consequences);
+//! }
+//! # });
+//! ```
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    sequence::GenomicSequence,
+    transcript::{make_consequences, ConsequenceError, Consequences, Transcript},
+    vep::{TranscriptConsequenceResponse, VEPAnalysis},
+    Client, EnsemblError,
+};
+
+/// A variant with its VEP analysis and the consequences computed for each transcript it affects.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct AnnotatedVariant {
+    pub vep: VEPAnalysis,
+    pub transcripts: Vec<AnnotatedTranscript>,
+    /// The transcripts that couldn't be annotated because they or their sequence failed to load, or
+    /// their consequences couldn't be computed. The latter have a `status_code` of 0.
+    pub errors: Vec<EnsemblError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct AnnotatedTranscript {
+    pub transcript_consequence: TranscriptConsequenceResponse,
+    pub transcript: Transcript,
+    /// One entry per alternate allele, see [allele_consequences].
+    pub consequences: Vec<Consequences>,
+}
+
+/// Run VEP on `hgvs`, then fetch and compute the consequences for every transcript it reports.
+/// * Transcript consequences VEP returned in a shape this crate can't parse are still annotated,
+///   as long as they name their transcript.
+/// * Transcripts are fetched concurrently, so clients should be from long lived [crate::Getter]s.
+/// # Errors
+///
+/// Returns the [EnsemblError] if the VEP analysis fails. Failures for individual transcripts are
+/// collected in [AnnotatedVariant::errors].
+pub async fn annotate_variant(
+    hgvs: &str,
+    vep: Client<'static, VEPAnalysis>,
+    transcripts: Client<'static, Transcript>,
+    sequences: Client<'static, GenomicSequence>,
+) -> Result<AnnotatedVariant, EnsemblError> {
+    let vep = vep.get(hgvs.to_owned()).await?;
+    let handles: Vec<_> = vep
+        .transcript_consequences
+        .iter()
+        .filter_map(|tc| {
+            let transcripts = transcripts.clone();
+            let sequences = sequences.clone();
+            let id = tc.transcript_id()?.to_owned();
+            let handle = tokio::spawn(async move {
+                let transcript = transcripts.get(id.clone()).await?;
+                let seq = sequences.get(id).await?;
+                Ok::<_, EnsemblError>((transcript, seq))
+            });
+            Some((tc.clone(), handle))
+        })
+        .collect();
+    let mut annotated = AnnotatedVariant {
+        vep,
+        transcripts: Vec::new(),
+        errors: Vec::new(),
+    };
+    for (tc, handle) in handles.into_iter() {
+        let (transcript, seq) = match handle.await.expect("Transcript task panicked") {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                annotated.errors.push(err);
+                continue;
+            }
+        };
+        match allele_consequences(&annotated.vep, &transcript, &seq) {
+            Ok(consequences) => annotated.transcripts.push(AnnotatedTranscript {
+                transcript_consequence: tc,
+                consequences,
+                transcript,
+            }),
+            Err(err) => annotated.errors.push(EnsemblError {
+                input: transcript.id,
+                error: err.to_string(),
+                status_code: 0,
+                retries: 0,
+            }),
+        }
+    }
+    Ok(annotated)
+}
+
+/// The [Consequences] of each of `vep`'s alternate alleles on `transcript`, in [crate::vep::Allele::variants] order.
+/// * `seq` is the [GenomicSequence] of `transcript`.
+/// * Empty if the variant isn't strictly within the transcript, eg upstream or downstream variants.
+/// # Errors
+///
+/// Returns the first [ConsequenceError] from [make_consequences].
+pub fn allele_consequences(
+    vep: &VEPAnalysis,
+    transcript: &Transcript,
+    seq: &GenomicSequence,
+) -> Result<Vec<Consequences>, ConsequenceError> {
+    // Insertions are reported with the start one past the end.
+    let (first, last) = (vep.start.min(vep.end), vep.start.max(vep.end));
+    if first <= transcript.start || last >= transcript.end {
+        return Ok(Vec::new());
+    }
+    vep.allele
+        .variants
+        .iter()
+        .map(|allele| make_consequences(seq, transcript, vep.start, vep.end, allele))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vep_at(start: u32, end: u32, allele_string: &str) -> VEPAnalysis {
+        serde_json::from_value(serde_json::json!({
+            "input": "test",
+            "strand": 1,
+            "assembly_name": "GRCh38",
+            "seq_region_name": "1",
+            "most_severe_consequence": "missense_variant",
+            "start": start,
+            "end": end,
+            "allele_string": allele_string,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_allele_consequences() {
+        let transcript: Transcript = serde_json::from_str(
+            r#"{"id":"ENST1","start":101,"end":118,"strand":1,"species":"homo_sapiens","Translation":{"id":"ENSP1","start":101,"end":118,"length":5}}"#,
+        )
+        .unwrap();
+        let seq = GenomicSequence {
+            query: "ENST1".to_owned(),
+            id: "ENST1".to_owned(),
+            desc: None,
+            seq: "ATGAAAgtaagCCCTAAA".to_owned(),
+        };
+        let coding = allele_consequences(&vep_at(104, 104, "A/G/C"), &transcript, &seq).unwrap();
+        assert_eq!(coding.len(), 2);
+        assert!(matches!(coding[0], Consequences::Coding { .. }));
+        let intron = allele_consequences(&vep_at(109, 109, "a/t"), &transcript, &seq).unwrap();
+        assert_eq!(intron, [Consequences::Intron]);
+        assert_eq!(
+            allele_consequences(&vep_at(90, 90, "A/G"), &transcript, &seq),
+            Ok(Vec::new())
+        );
+        assert_eq!(
+            allele_consequences(&vep_at(118, 118, "A/G"), &transcript, &seq),
+            Ok(Vec::new())
+        );
+        assert_eq!(
+            allele_consequences(&vep_at(104, 104, "A/R"), &transcript, &seq),
+            Err(ConsequenceError::InvalidAllele("R".to_owned()))
+        );
+    }
+}
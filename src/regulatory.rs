@@ -0,0 +1,94 @@
+//! Regulatory features from the Ensembl Regulatory Build, eg the promoters and enhancers reported by
+//! [crate::vep::VEPAnalysis::regulatory_feature_consequences], from the
+//! `/regulatory/species/:species/id` endpoint.
+//! ```no_run
+//! # tokio::runtime::Builder::new_current_thread()
+//! #       .enable_all()
+//! #       .build()
+//! #       .unwrap()
+//! #       .block_on(async {
+//! use rs_embl::regulatory::regulatory_feature;
+//!
+//! let client = reqwest::Client::new();
+//! let feature = regulatory_feature(&client, "homo_sapiens", "ENSR00000105783")
+//!     .await
+//!     .unwrap();
+//! println!("{} {:?}", feature.feature_type, feature.description);
+//! # });
+//! ```
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{get_json, EnsemblError, Transport, ENSEMBL_SERVER};
+
+/// A regulatory feature looked up by its stable identifier, eg `"ENSR00000105783"`.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct RegulatoryFeature {
+    pub id: String,
+    /// eg "Promoter", "Enhancer" or "CTCF Binding Site".
+    pub feature_type: String,
+    pub description: Option<String>,
+    /// The extent of the feature including its flanking region.
+    pub bound_start: u32,
+    pub bound_end: u32,
+    pub start: Option<u32>,
+    pub end: Option<u32>,
+    pub seq_region_name: Option<String>,
+    /// The activity of the feature keyed by epigenome, eg `"ACTIVE"` or `"POISED"`.
+    #[serde(default)]
+    pub activity: BTreeMap<String, String>,
+}
+
+/// Get the regulatory feature `id` in `species`, with its activity in each epigenome.
+/// # Errors
+///
+/// Returns an [EnsemblError] for `id` if the request fails, Ensembl reports an error or returns no
+/// feature.
+pub async fn regulatory_feature(
+    transport: &dyn Transport,
+    species: &str,
+    id: &str,
+) -> Result<RegulatoryFeature, EnsemblError> {
+    let url = format!("{ENSEMBL_SERVER}/regulatory/species/{species}/id/{id}?activity=1");
+    let features: Vec<RegulatoryFeature> = get_json(transport, &url, id).await?;
+    features.into_iter().next().ok_or_else(|| EnsemblError {
+        input: id.to_owned(),
+        error: "Ensembl did not return a regulatory feature".to_owned(),
+        status_code: 200,
+        retries: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GetFixture;
+
+    const PROMOTER_JSON: &str = r#"[{"id":"ENSR00000105783","feature_type":"Promoter","description":"Predicted promoter","bound_start":31590601,"bound_end":31592800,"start":31591001,"end":31592600,"seq_region_name":"18","activity":{"liver":"ACTIVE","HeLa-S3":"INACTIVE"}}]"#;
+
+    #[tokio::test]
+    async fn test_regulatory_feature() {
+        let transport = GetFixture::new(PROMOTER_JSON);
+        let feature = regulatory_feature(&transport, "homo_sapiens", "ENSR00000105783")
+            .await
+            .unwrap();
+        assert_eq!(
+            transport.urls()[0],
+            "https://rest.ensembl.org/regulatory/species/homo_sapiens/id/ENSR00000105783?activity=1"
+        );
+        assert_eq!(feature.id, "ENSR00000105783");
+        assert_eq!(feature.feature_type, "Promoter");
+        assert!(feature.bound_start < feature.start.unwrap());
+        assert_eq!(feature.activity["liver"], "ACTIVE");
+        let json = serde_json::to_string(&feature).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RegulatoryFeature>(&json).unwrap(),
+            feature
+        );
+        let err = regulatory_feature(&GetFixture::new("[]"), "homo_sapiens", "ENSR1")
+            .await
+            .unwrap_err();
+        assert_eq!(err.input, "ENSR1");
+    }
+}
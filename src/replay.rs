@@ -0,0 +1,268 @@
+//! Serve recorded Ensembl responses without a network, for deterministic tests of pipelines built
+//! on this crate.
+//! * A [ReplayTransport] is supplied to a [crate::Getter] with [crate::GetterBuilder::transport], or
+//!   used directly with the GET only helpers, eg [crate::homology::homologies].
+//! * [ReplayTransport::recording] forwards requests without a fixture to another transport and
+//!   saves the response, so fixtures can be recorded from a live run then committed.
+//! * Payloads list their ids in sorted order, so a batch of the same ids always maps to the same
+//!   fixture. Which ids share a batch still depends on timing, eg requests queued together with
+//!   [crate::Client::get_many] are batched the same way on every run.
+//! # Fixture format
+//!
+//! Each request is stored in `<dir>/<key>.json`, where `<key>` is [fixture_key] of the request's
+//! method, url and body. Headers are not part of the key. The file holds one JSON object:
+//! ```json
+//! {
+//!   "method": "POST",
+//!   "url": "https://rest.ensembl.org/variation/homo_sapiens",
+//!   "body": "{\"ids\" : [\"rs28933979\"]}",
+//!   "status": 200,
+//!   "response": "{\"rs28933979\":{\"name\":\"rs28933979\"}}"
+//! }
+//! ```
+//! * `body` is empty for GET requests.
+//! * `response` is the raw response body, as a string, so error responses replay verbatim.
+//! ```no_run
+//! # tokio::runtime::Builder::new_current_thread()
+//! #       .enable_all()
+//! #       .build()
+//! #       .unwrap()
+//! #       .block_on(async {
+//! use rs_embl::{replay::ReplayTransport, variation::Variation, Getter};
+//!
+//! // Record fixtures missing from tests/fixtures, then serve them offline on later runs.
+//! let transport = ReplayTransport::recording("tests/fixtures", reqwest::Client::new());
+//! let getter = Getter::<Variation>::builder().transport(transport).build();
+//! let variation = getter.client().get("rs28933979".to_owned()).await.unwrap();
+//! # });
+//! ```
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::{Transport, TransportFuture};
+
+/// A recorded request and the response Ensembl gave it, see the [module docs](self) for the format.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct Fixture {
+    /// "POST" or "GET".
+    pub method: String,
+    pub url: String,
+    /// The posted payload, empty for GET requests.
+    #[serde(default)]
+    pub body: String,
+    pub status: u16,
+    pub response: String,
+}
+
+/// A [Transport] answering requests from fixtures in a directory instead of the network.
+/// * Fixture files are read and written synchronously, which is fine for tests but not meant for
+///   production use.
+#[derive(Clone)]
+pub struct ReplayTransport {
+    dir: PathBuf,
+    record: Option<Arc<dyn Transport>>,
+}
+impl std::fmt::Debug for ReplayTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ReplayTransport")
+            .field("dir", &self.dir)
+            .field("recording", &self.record.is_some())
+            .finish()
+    }
+}
+impl ReplayTransport {
+    /// Serve fixtures from `dir`. Requests without a fixture fail.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            record: None,
+        }
+    }
+
+    /// Serve fixtures from `dir`, forwarding requests without one to `transport` and saving its
+    /// response as a new fixture.
+    /// * Responses are saved whatever their status, but failed requests are not.
+    pub fn recording(dir: impl Into<PathBuf>, transport: impl Transport + 'static) -> Self {
+        Self {
+            dir: dir.into(),
+            record: Some(Arc::new(transport)),
+        }
+    }
+
+    /// The path of the fixture for a request.
+    pub fn fixture_path(&self, method: &str, url: &str, body: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.json", fixture_key(method, url, body)))
+    }
+
+    async fn replay(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: String,
+    ) -> anyhow::Result<(u16, String)> {
+        let path = self.fixture_path(method, url, &body);
+        if path.exists() {
+            let fixture = read_fixture(&path)?;
+            if fixture.method != method || fixture.url != url || fixture.body != body {
+                bail!(
+                    "Fixture {} is for {} {}, not {method} {url}",
+                    path.display(),
+                    fixture.method,
+                    fixture.url
+                );
+            }
+            return Ok((fixture.status, fixture.response));
+        }
+        let Some(transport) = &self.record else {
+            bail!("No fixture {} for {method} {url}: {body}", path.display());
+        };
+        let (status, response) = match method {
+            "GET" => transport.get(url, headers).await?,
+            _ => transport.post(url, headers, body.clone()).await?,
+        };
+        let fixture = Fixture {
+            method: method.to_owned(),
+            url: url.to_owned(),
+            body,
+            status,
+            response,
+        };
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        std::fs::write(&path, serde_json::to_string_pretty(&fixture)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok((fixture.status, fixture.response))
+    }
+}
+impl Transport for ReplayTransport {
+    fn post<'a>(
+        &'a self,
+        url: &'a str,
+        headers: &'a [(&'a str, &'a str)],
+        body: String,
+    ) -> TransportFuture<'a> {
+        Box::pin(self.replay("POST", url, headers, body))
+    }
+
+    fn get<'a>(&'a self, url: &'a str, headers: &'a [(&'a str, &'a str)]) -> TransportFuture<'a> {
+        Box::pin(self.replay("GET", url, headers, String::new()))
+    }
+}
+
+fn read_fixture(path: &Path) -> anyhow::Result<Fixture> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("Malformed fixture {}", path.display()))
+}
+
+/// The file stem of the fixture for a request: the 64 bit FNV-1a hash of its method, url and body,
+/// as 16 lowercase hex digits.
+/// * Stable across platforms and Rust versions, so fixtures can be committed.
+pub fn fixture_key(method: &str, url: &str, body: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in [method, url, body] {
+        // Separate the parts so moving bytes between them changes the key.
+        for byte in part.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{variation::Variation, Getter};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts its posts, answering each with the same variation.
+    struct CountingTransport(Arc<AtomicUsize>);
+    impl Transport for CountingTransport {
+        fn post<'a>(
+            &'a self,
+            _url: &'a str,
+            _headers: &'a [(&'a str, &'a str)],
+            _body: String,
+        ) -> TransportFuture<'a> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async {
+                Ok((
+                    200,
+                    r#"{"rs28933979":{"name":"rs28933979","minor_allele":"A"}}"#.to_owned(),
+                ))
+            })
+        }
+    }
+
+    fn fixture_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rs-embl-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_fixture_key() {
+        assert_eq!(fixture_key("GET", "url", ""), fixture_key("GET", "url", ""));
+        assert_ne!(fixture_key("GET", "url", ""), fixture_key("GET", "ur", "l"));
+        assert_eq!(fixture_key("POST", "url", "body").len(), 16);
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let dir = fixture_dir("record");
+        let posts = Arc::new(AtomicUsize::new(0));
+        let getter = Getter::<Variation>::builder()
+            .transport(ReplayTransport::recording(
+                &dir,
+                CountingTransport(posts.clone()),
+            ))
+            .build();
+        let recorded = getter.client().get("rs28933979".to_owned()).await.unwrap();
+        assert_eq!(posts.load(Ordering::SeqCst), 1);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        let getter = Getter::<Variation>::builder()
+            .transport(ReplayTransport::new(&dir))
+            .build();
+        let replayed = getter.client().get("rs28933979".to_owned()).await.unwrap();
+        assert_eq!(replayed, recorded);
+        assert_eq!(replayed.minor_allele.as_deref(), Some("A"));
+        let missing = getter.client().get("rs1".to_owned()).await.unwrap_err();
+        assert!(missing.error.contains("No fixture"), "{}", missing.error);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_fixture() {
+        let dir = fixture_dir("get");
+        let transport = ReplayTransport::new(&dir);
+        let url = "https://rest.ensembl.org/info/ping";
+        let fixture = Fixture {
+            method: "GET".to_owned(),
+            url: url.to_owned(),
+            body: String::new(),
+            status: 200,
+            response: r#"{"ping":1}"#.to_owned(),
+        };
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            transport.fixture_path("GET", url, ""),
+            serde_json::to_string(&fixture).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            transport.get(url, &[]).await.unwrap(),
+            (200, r#"{"ping":1}"#.to_owned())
+        );
+        assert!(transport.post(url, &[], String::new()).await.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
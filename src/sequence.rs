@@ -1,7 +1,13 @@
 //! Structures for the Sequence endpoint of the Ensembl API.
+//! * `/sequence/id` takes a single sequence type per request, so [CdnaSequence], [GenomicSequence] and
+//!   [CodingSequence] can't share a batch and each needs its own [Getter]. [SequenceGetter] bundles the three.
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{Client, EnsemblError, Getter, GetterBuilder};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
 pub struct CdnaSequence {
     pub query: String,
     pub id: String,
@@ -9,7 +15,7 @@ pub struct CdnaSequence {
     pub seq: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
 pub struct GenomicSequence {
     pub query: String,
     pub id: String,
@@ -17,7 +23,7 @@ pub struct GenomicSequence {
     pub seq: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
 pub struct CodingSequence {
     pub query: String,
     pub id: String,
@@ -56,32 +62,367 @@ impl GenomicSequence {
     /// # });
     ///```
     pub fn exons(&self) -> Vec<&str> {
-        let mut output = Vec::new();
-        let mut start = 0;
-        let mut end = 1;
-        let mut is_upper = char::from(self.seq.as_bytes()[start]).is_uppercase();
-        while end < self.seq.len() {
-            if is_upper == char::from(self.seq.as_bytes()[end]).is_uppercase() {
-                end += 1;
-                continue;
-            } else {
-                if is_upper {
-                    output.push(&self.seq[start..end]);
-                }
-                start = end;
-                is_upper = char::from(self.seq.as_bytes()[start]).is_uppercase();
-                end += 1;
-            }
+        upper_case_runs(&self.seq)
+    }
+
+    /// The [exons](GenomicSequence::exons) spliced together, with the offset of each exon-exon
+    /// junction in the spliced sequence.
+    /// * The offsets are the cumulative exon lengths, so a junction `j` falls between
+    ///   `spliced[..j]` and `spliced[j..]`. A single exon sequence has none.
+    /// * See [crate::transcript::translate_spliced] to predict nonsense mediated decay from them.
+    /// ```
+    /// use rs_embl::sequence::*;
+    /// let test_seq = GenomicSequence{
+    /// query: "".to_owned(),
+    /// id: "".to_owned(),
+    /// desc: None,
+    /// seq: "acATGgtCCCCagTAAacgt".to_owned(),
+    /// };
+    /// assert_eq!(test_seq.spliced_with_junctions(), ("ATGCCCCTAA".to_owned(), vec![3, 7]));
+    /// ```
+    pub fn spliced_with_junctions(&self) -> (String, Vec<usize>) {
+        splice(&self.seq)
+    }
+}
+
+/// The upper case runs of `seq` concatenated, with the cumulative length at each junction between them.
+pub(crate) fn splice(seq: &str) -> (String, Vec<usize>) {
+    let mut spliced = String::with_capacity(seq.len());
+    let mut junctions = Vec::new();
+    for exon in upper_case_runs(seq) {
+        if !spliced.is_empty() {
+            junctions.push(spliced.len());
         }
-        if end > 1 {
+        spliced.push_str(exon);
+    }
+    (spliced, junctions)
+}
+
+/// The runs of upper case bases in `seq`, in order.
+fn upper_case_runs(seq: &str) -> Vec<&str> {
+    let mut output = Vec::new();
+    if seq.is_empty() {
+        return output;
+    }
+    let mut start = 0;
+    let mut end = 1;
+    let mut is_upper = char::from(seq.as_bytes()[start]).is_uppercase();
+    while end < seq.len() {
+        if is_upper == char::from(seq.as_bytes()[end]).is_uppercase() {
+            end += 1;
+            continue;
+        } else {
             if is_upper {
-                output.push(&self.seq[start..]);
+                output.push(&seq[start..end]);
             }
+            start = end;
+            is_upper = char::from(seq.as_bytes()[start]).is_uppercase();
+            end += 1;
+        }
+    }
+    if end > 1 && is_upper {
+        output.push(&seq[start..]);
+    }
+    output
+}
+
+/// The sequence of an arbitrary human genomic region, from the `/sequence/region` endpoint.
+/// * Regions are requested as `chr:start..end`, optionally followed by `:1` or `:-1` for the strand,
+///   eg `X:1000000..1000100:1`. `chr:start-end` is also accepted.
+/// * With [GetterBuilder::soft_mask], repeats are returned in lower case and [RegionSequence::unmasked]
+///   splits them out, as [GenomicSequence::exons] does for introns.
+/// ```
+/// use rs_embl::{sequence::RegionSequence, EnsemblPostEndpoint};
+/// assert!(RegionSequence::check_input("X:1000000..1000100:-1").is_ok());
+/// assert!(RegionSequence::check_input("ENST00000237014").is_err());
+/// let region = RegionSequence {
+///     query: "X:1000000..1000011".to_owned(),
+///     id: "chromosome:GRCh38:X:1000000:1000011:1".to_owned(),
+///     seq: "ACGTacgtACGT".to_owned(),
+/// };
+/// assert_eq!(region.unmasked(), vec!["ACGT", "ACGT"]);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct RegionSequence {
+    /// The region as requested.
+    pub query: String,
+    /// The region as resolved by Ensembl, eg `chromosome:GRCh38:X:1000000:1000100:1`.
+    pub id: String,
+    pub seq: String,
+}
+impl RegionSequence {
+    /// The runs of the sequence that aren't soft masked, see [GetterBuilder::soft_mask].
+    pub fn unmasked(&self) -> Vec<&str> {
+        upper_case_runs(&self.seq)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl GetterBuilder<RegionSequence> {
+    /// Return repeats in lower case, as split out by [RegionSequence::unmasked].
+    pub fn soft_mask(self) -> Self {
+        self.payload_template(r#"{"mask" : "soft", "regions" : {ids}}"#.to_owned())
+    }
+}
+
+/// One [Getter] per sequence type, for pipelines needing several types of sequence for the same transcripts.
+/// * Requests are batched with others of the same type. Each type has its own polling task, so a
+///   [GetterBuilder::max_requests_per_second] limit applies to each Getter separately.
+/// ```no_run
+/// # tokio::runtime::Builder::new_current_thread()
+/// #       .enable_all()
+/// #       .build()
+/// #       .unwrap()
+/// #       .block_on(async {
+/// use rs_embl::sequence::SequenceGetter;
+///
+/// let sequences = SequenceGetter::new();
+/// let client = sequences.client();
+/// let cdna = client.cdna("ENST00000237014".to_owned()).await.unwrap();
+/// let genomic = client.genomic("ENST00000237014".to_owned()).await.unwrap();
+/// assert_eq!(cdna.seq, genomic.exons().concat());
+/// # });
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Default)]
+pub struct SequenceGetter {
+    pub cdna: Getter<CdnaSequence>,
+    pub genomic: Getter<GenomicSequence>,
+    pub coding: Getter<CodingSequence>,
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl SequenceGetter {
+    /// Create a default [Getter] for each sequence type.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a [SequenceClient] that can be sent across async tasks.
+    pub fn client(&self) -> SequenceClient {
+        SequenceClient {
+            cdna: self.cdna.client(),
+            genomic: self.genomic.client(),
+            coding: self.coding.client(),
+        }
+    }
+}
+
+/// A [Client] for each [Getter] of a [SequenceGetter].
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct SequenceClient {
+    cdna: Client<'static, CdnaSequence>,
+    genomic: Client<'static, GenomicSequence>,
+    coding: Client<'static, CodingSequence>,
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl SequenceClient {
+    /// As [Client::get] for a [CdnaSequence].
+    /// # Errors
+    ///
+    /// As [Client::get].
+    pub async fn cdna(&self, id: String) -> Result<CdnaSequence, EnsemblError> {
+        self.cdna.clone().get(id).await
+    }
+
+    /// As [Client::get] for a [GenomicSequence].
+    /// # Errors
+    ///
+    /// As [Client::get].
+    pub async fn genomic(&self, id: String) -> Result<GenomicSequence, EnsemblError> {
+        self.genomic.clone().get(id).await
+    }
+
+    /// As [Client::get] for a [CodingSequence].
+    /// # Errors
+    ///
+    /// As [Client::get].
+    pub async fn coding(&self, id: String) -> Result<CodingSequence, EnsemblError> {
+        self.coding.clone().get(id).await
+    }
+}
+
+/// The default line width used by [Fasta::to_fasta].
+pub const FASTA_LINE_WIDTH: usize = 60;
+
+/// Export a sequence as a FASTA record.
+/// ```
+/// use rs_embl::sequence::*;
+/// let test_seq = CdnaSequence {
+///     query: "ENST00000237014".to_owned(),
+///     id: "ENST00000237014.8".to_owned(),
+///     desc: Some("chromosome:GRCh38:18:31591877:31599023:1".to_owned()),
+///     seq: "ACGTACGTAC".to_owned(),
+/// };
+/// assert_eq!(
+///     test_seq.to_fasta_wrapped(4),
+///     ">ENST00000237014.8 chromosome:GRCh38:18:31591877:31599023:1\nACGT\nACGT\nAC\n"
+/// );
+/// ```
+pub trait Fasta {
+    /// The text following the `>` of the record header.
+    fn fasta_header(&self) -> String;
+    /// The sequence to be written in the record body.
+    fn fasta_sequence(&self) -> &str;
+    /// Format as a FASTA record wrapped at [FASTA_LINE_WIDTH] columns.
+    fn to_fasta(&self) -> String {
+        self.to_fasta_wrapped(FASTA_LINE_WIDTH)
+    }
+    /// Format as a FASTA record wrapped at `width` columns. A `width` of 0 disables wrapping.
+    fn to_fasta_wrapped(&self, width: usize) -> String {
+        let seq = self.fasta_sequence();
+        let mut output = format!(">{}\n", self.fasta_header());
+        if width == 0 {
+            output.push_str(seq);
+            output.push('\n');
+            return output;
+        }
+        for line in seq.as_bytes().chunks(width) {
+            output.push_str(&String::from_utf8_lossy(line));
+            output.push('\n');
         }
         output
     }
 }
 
+fn fasta_header(query: &str, id: &str, desc: &Option<String>) -> String {
+    let name = if id.is_empty() { query } else { id };
+    match desc {
+        Some(desc) if !desc.is_empty() => format!("{name} {desc}"),
+        _ => name.to_owned(),
+    }
+}
+
+impl Fasta for CdnaSequence {
+    fn fasta_header(&self) -> String {
+        fasta_header(&self.query, &self.id, &self.desc)
+    }
+    fn fasta_sequence(&self) -> &str {
+        &self.seq
+    }
+}
+impl Fasta for GenomicSequence {
+    fn fasta_header(&self) -> String {
+        fasta_header(&self.query, &self.id, &self.desc)
+    }
+    fn fasta_sequence(&self) -> &str {
+        &self.seq
+    }
+}
+impl Fasta for RegionSequence {
+    fn fasta_header(&self) -> String {
+        fasta_header(&self.query, &self.id, &None)
+    }
+    fn fasta_sequence(&self) -> &str {
+        &self.seq
+    }
+}
+impl Fasta for CodingSequence {
+    fn fasta_header(&self) -> String {
+        fasta_header(&self.query, &self.id, &self.desc)
+    }
+    fn fasta_sequence(&self) -> &str {
+        &self.seq
+    }
+}
+
+/// Bases of flanking sequence to request either side of a [GenomicSequence].
+/// * Flanks are given in the orientation of the feature, so `five_prime` bases always lead the returned `seq`.
+/// * Coordinate arithmetic against the [crate::transcript::Transcript] must skip the `five_prime` bases,
+///   or use [GenomicSequence::without_flanks] to recover the unexpanded sequence.
+/// ```
+/// use rs_embl::{Getter, sequence::*};
+/// let flanks = Flanks { five_prime: 3, three_prime: 2 };
+/// # tokio::runtime::Builder::new_current_thread()
+/// #       .enable_all()
+/// #       .build()
+/// #       .unwrap()
+/// #       .block_on(async {
+/// let v = Getter::<GenomicSequence>::builder().flanks(flanks).build();
+/// # });
+/// let flanked = GenomicSequence {
+///     query: "".to_owned(),
+///     id: "".to_owned(),
+///     desc: None,
+///     seq: "ttgACGTacgtACGTca".to_owned(),
+/// };
+/// // Position 4 of the transcript sits 3 bases further into the flanked sequence.
+/// assert_eq!(flanked.seq.as_bytes()[4 + 3], b'a');
+/// let trimmed = flanked.without_flanks(&flanks);
+/// assert_eq!(trimmed.seq, "ACGTacgtACGT");
+/// assert_eq!(trimmed.seq.as_bytes()[4], b'a');
+/// assert_eq!(trimmed.exons(), vec!["ACGT", "ACGT"]);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Flanks {
+    pub five_prime: u32,
+    pub three_prime: u32,
+}
+impl Flanks {
+    fn payload_template(&self) -> String {
+        format!(
+            r#"{{"type": "genomic", "mask_feature" : 1, "expand_5prime" : {}, "expand_3prime" : {}, "ids" : {{ids}}}}"#,
+            self.five_prime, self.three_prime
+        )
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl GetterBuilder<GenomicSequence> {
+    /// Request `flanks` bases of sequence beyond either end of each feature.
+    pub fn flanks(self, flanks: Flanks) -> Self {
+        self.payload_template(flanks.payload_template())
+    }
+}
+
+impl GenomicSequence {
+    /// Strip the flanking bases requested with [GetterBuilder::flanks],
+    /// leaving a sequence whose coordinates start at the feature start again.
+    pub fn without_flanks(&self, flanks: &Flanks) -> GenomicSequence {
+        let start = (flanks.five_prime as usize).min(self.seq.len());
+        let end = self
+            .seq
+            .len()
+            .saturating_sub(flanks.three_prime as usize)
+            .max(start);
+        GenomicSequence {
+            query: self.query.clone(),
+            id: self.id.clone(),
+            desc: self.desc.clone(),
+            seq: self.seq[start..end].to_owned(),
+        }
+    }
+
+    /// A copy of the sequence on the opposite strand, with its case preserved so
+    /// [GenomicSequence::exons] still finds the same exons, in reverse order.
+    /// * Ensembl already returns a transcript's sequence in transcription orientation, so this is
+    ///   for sequences fetched on the forward strand, eg a minus strand transcript's region.
+    /// # Panics
+    ///
+    /// Panics if the sequence contains anything other than `ACGTN`, in either case.
+    /// ```
+    /// use rs_embl::sequence::GenomicSequence;
+    /// let seq = GenomicSequence {
+    ///     query: "".to_owned(),
+    ///     id: "".to_owned(),
+    ///     desc: None,
+    ///     seq: "acATGacgtACGGacgt".to_owned(),
+    /// };
+    /// let reversed = seq.reverse_complemented();
+    /// assert_eq!(reversed.seq, "acgtCCGTacgtCATgt");
+    /// assert_eq!(reversed.exons(), vec!["CCGT", "CAT"]);
+    /// assert_eq!(reversed.exons().len(), seq.exons().len());
+    /// ```
+    pub fn reverse_complemented(&self) -> GenomicSequence {
+        GenomicSequence {
+            query: self.query.clone(),
+            id: self.id.clone(),
+            desc: self.desc.clone(),
+            seq: crate::transcript::reverse_complement(&self.seq),
+        }
+    }
+}
+
 impl crate::EnsemblPostEndpoint for CodingSequence {
     fn extension() -> &'static str {
         "/sequence/id"
@@ -115,3 +456,24 @@ impl crate::EnsemblPostEndpoint for GenomicSequence {
         &self.query
     }
 }
+impl crate::EnsemblPostEndpoint for RegionSequence {
+    fn extension() -> &'static str {
+        "/sequence/region/human"
+    }
+    fn payload_template() -> &'static str {
+        r#"{"regions" : {ids}}"#
+    }
+    fn input(&self) -> &str {
+        &self.query
+    }
+    fn check_input(input: &str) -> Result<(), String> {
+        let region = Regex::new(r"^[^:\s]+:[0-9]+(\.\.|-)[0-9]+(:-?1)?$").unwrap();
+        if region.is_match(input) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected a region like `X:1000000..1000100:1`, got `{input}`"
+            ))
+        }
+    }
+}
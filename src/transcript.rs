@@ -1,17 +1,17 @@
+use std::str::FromStr;
+
 use itertools::Itertools;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::{
-    sequence::{CdnaSequence, GenomicSequence},
-    Client,
+    sequence::{splice, CdnaSequence, GenomicSequence},
+    Client, Strand,
 };
 
 const LAST_EJC_REGEX: &str = r".+([A-Z][a-z]+[A-Z]+)$";
 
-/**
-
-*/
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
 pub struct Transcript {
     pub id: String,
@@ -19,7 +19,7 @@ pub struct Transcript {
     pub display_name: String,
     pub start: u32,
     pub end: u32,
-    pub strand: i8,
+    pub strand: Strand,
     #[serde(rename = "Translation")]
     pub translation: Option<Translation>,
     #[serde(rename = "UTR", default)]
@@ -31,6 +31,9 @@ pub struct Transcript {
     pub species: String,
     #[serde(default)]
     pub biotype: crate::Biotype,
+    /// The chromosome or other sequence region the transcript is on, eg "18" or "MT".
+    #[serde(default)]
+    pub seq_region_name: String,
 }
 impl Transcript {
     pub async fn cdna_sequence(&self, client: Client<'static, CdnaSequence>) -> CdnaSequence {
@@ -51,6 +54,300 @@ impl Transcript {
             .unwrap()
             .unwrap()
     }
+
+    /// Whether the transcript encodes a protein: a coding [crate::Biotype] with a translation.
+    /// * False for `protein_coding_CDS_not_defined`, whose transcripts have no usable translation.
+    pub fn is_coding(&self) -> bool {
+        self.biotype.is_coding() && self.translation.is_some()
+    }
+
+    /// Whether the translation starts and ends within exons of the transcript, with the start
+    /// before the end.
+    /// * False without a translation, or if no exons were returned to check it against.
+    pub fn has_valid_translation(&self) -> bool {
+        let Some(translation) = &self.translation else {
+            return false;
+        };
+        let in_exon = |pos: u32| {
+            self.exons
+                .iter()
+                .any(|exon| exon.start <= pos && pos <= exon.end)
+        };
+        translation.start <= translation.end
+            && self.start <= translation.start
+            && translation.end <= self.end
+            && in_exon(translation.start)
+            && in_exon(translation.end)
+    }
+
+    /// The exons from the 5' to the 3' end of the transcript, ie by descending coordinates on the
+    /// minus strand.
+    /// * [Transcript::exons] are sorted by their coordinates first, whatever order they were returned in.
+    pub fn exons_in_transcription_order(&self) -> Vec<&Exon> {
+        let mut exons: Vec<&Exon> = self.exons.iter().collect();
+        exons.sort_by_key(|exon| exon.start);
+        if self.strand != Strand::PLUS {
+            exons.reverse();
+        }
+        exons
+    }
+
+    /// The offset of the first base of the start codon within the [GenomicSequence] of this transcript.
+    /// * On the minus strand this is measured from the transcript end, as Ensembl returns the sequence
+    ///   in the orientation of the transcript.
+    /// * Returns [None] if the transcript has no translation, or the translation starts outside it.
+    pub fn cds_offset_in_genomic(&self) -> Option<usize> {
+        let translation = self.translation.as_ref()?;
+        Some(if self.strand == Strand::PLUS {
+            translation.start.checked_sub(self.start)? as usize
+        } else {
+            self.end.checked_sub(translation.end)? as usize
+        })
+    }
+
+    /// The coding sequence of the transcript from the start codon through the stop codon.
+    /// * `seq` is the masked [GenomicSequence] of this transcript. Ensembl returns it in the orientation
+    ///   of the transcript, so minus strand coordinates are mirrored rather than reverse complemented.
+    /// * Returns an empty string if the transcript has no translation.
+    pub fn coding_sequence(&self, seq: &GenomicSequence) -> String {
+        let (Some(translation), Some(cds_start)) =
+            (&self.translation, self.cds_offset_in_genomic())
+        else {
+            return String::new();
+        };
+        let cds_end = cds_start + (translation.end - translation.start) as usize;
+        seq.seq
+            .get(cds_start..=cds_end)
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| c.is_uppercase())
+            .collect()
+    }
+
+    /// How closely the start codon's context matches the Kozak consensus `gccRccATGG`.
+    /// * `seq` is the unflanked [GenomicSequence] of this transcript, as for [Transcript::coding_sequence].
+    ///   The -3 position is read from the spliced 5' UTR, so it may lie in an earlier exon.
+    /// * Returns [None] if there is no translation, the coding sequence doesn't start with ATG, or
+    ///   fewer than three bases of 5' UTR are annotated.
+    pub fn kozak_strength(&self, seq: &GenomicSequence) -> Option<KozakStrength> {
+        let cds_start = self.cds_offset_in_genomic()?;
+        let upstream: Vec<char> = seq
+            .seq
+            .get(..cds_start)?
+            .chars()
+            .filter(|c| c.is_uppercase())
+            .collect();
+        let minus_three = *upstream.len().checked_sub(3).map(|i| &upstream[i])?;
+        let cds = self.coding_sequence(seq);
+        if !cds.starts_with("ATG") {
+            return None;
+        }
+        let plus_four = cds.chars().nth(3)?;
+        Some(KozakStrength::classify(minus_three, plus_four))
+    }
+
+    /// The codon table the transcript is translated with: [GeneticCode::VertebrateMitochondrial] on
+    /// the mitochondrial genome, otherwise [GeneticCode::Standard].
+    pub fn genetic_code(&self) -> GeneticCode {
+        match self.seq_region_name.as_str() {
+            "MT" | "chrM" | "M" => GeneticCode::VertebrateMitochondrial,
+            _ => GeneticCode::Standard,
+        }
+    }
+
+    /// Translate [Transcript::coding_sequence] with [Transcript::genetic_code].
+    /// * `seq` is the [GenomicSequence] of this transcript, as for [Transcript::coding_sequence].
+    /// # Panics
+    ///
+    /// As [translate].
+    pub fn translate(&self, seq: &GenomicSequence) -> TranslationConsequence {
+        translate_with(&self.coding_sequence(seq), self.genetic_code())
+    }
+
+    /// As [Transcript::translate], but also predicts nonsense mediated decay from the exon-exon
+    /// junctions of the spliced transcript, see [translate_spliced].
+    /// * Translation starts at the CDS start and runs to the first stop codon, which may lie past the
+    ///   annotated CDS end. [TranslationConsequence::stop_index] and
+    ///   [TranslationConsequence::last_ejc_index] are offsets into the spliced sequence from the CDS start.
+    /// # Panics
+    ///
+    /// As [translate].
+    pub fn translate_spliced(&self, seq: &GenomicSequence) -> TranslationConsequence {
+        let coding = self
+            .cds_offset_in_genomic()
+            .and_then(|cds_start| seq.seq.get(cds_start..))
+            .unwrap_or_default();
+        let (spliced, junctions) = splice(coding);
+        translate_spliced(&spliced, &junctions, self.genetic_code())
+    }
+
+    /// The 5' untranslated sequence of the transcript, across all of its 5' UTR exons.
+    /// * `seq` is the [GenomicSequence] of this transcript, as for [Transcript::coding_sequence].
+    /// * Returns an empty string if no 5' UTR is annotated.
+    pub fn five_prime_utr_sequence(&self, seq: &GenomicSequence) -> String {
+        self.utr_sequence(seq, UtrType::FivePrimeUtr)
+    }
+
+    /// The 3' untranslated sequence of the transcript, across all of its 3' UTR exons.
+    /// * `seq` is the [GenomicSequence] of this transcript, as for [Transcript::coding_sequence].
+    /// * Returns an empty string if no 3' UTR is annotated.
+    pub fn three_prime_utr_sequence(&self, seq: &GenomicSequence) -> String {
+        self.utr_sequence(seq, UtrType::ThreePrimeUtr)
+    }
+
+    fn utr_sequence(&self, seq: &GenomicSequence, utr_type: UtrType) -> String {
+        let mut utrs: Vec<&Utr> = self
+            .utrs
+            .iter()
+            .filter(|utr| utr.utr_type == utr_type)
+            .collect();
+        utrs.sort_by_key(|utr| utr.start);
+        if self.strand != Strand::PLUS {
+            utrs.reverse();
+        }
+        utrs.into_iter()
+            .filter_map(|utr| {
+                let (start, end) = if self.strand == Strand::PLUS {
+                    (
+                        utr.start.checked_sub(self.start)?,
+                        utr.end.checked_sub(self.start)?,
+                    )
+                } else {
+                    (
+                        self.end.checked_sub(utr.end)?,
+                        self.end.checked_sub(utr.start)?,
+                    )
+                };
+                seq.seq.get(start as usize..=end as usize)
+            })
+            .collect()
+    }
+
+    /// The 1-based position of `genomic_pos` in the spliced transcript, as VEP's `cdna_start`.
+    /// * Counts from the 5' end of the first exon in [Transcript::exons_in_transcription_order], so
+    ///   UTR positions are included.
+    /// * Returns [None] for intronic and out of transcript positions.
+    pub fn genomic_to_cdna_pos(&self, genomic_pos: u32) -> Option<u32> {
+        let mut cdna_offset = 0;
+        for exon in self.exons_in_transcription_order() {
+            if (exon.start..=exon.end).contains(&genomic_pos) {
+                cdna_offset += if self.strand == Strand::PLUS {
+                    genomic_pos - exon.start
+                } else {
+                    exon.end - genomic_pos
+                };
+                return Some(cdna_offset + 1);
+            }
+            cdna_offset += exon.length();
+        }
+        None
+    }
+
+    /// The 1-based amino acid position encoded by `genomic_pos`.
+    /// * Returns [None] for intronic, UTR and out of transcript positions, or if there is no translation.
+    /// * Positions within the stop codon map to one past the translation length, as VEP reports them.
+    pub fn genomic_to_protein_pos(&self, genomic_pos: u32) -> Option<u32> {
+        let translation = self.translation.as_ref()?;
+        if genomic_pos < translation.start || genomic_pos > translation.end {
+            return None;
+        }
+        let mut cds_offset = 0;
+        for exon in self.exons_in_transcription_order() {
+            let coding_start = exon.start.max(translation.start);
+            let coding_end = exon.end.min(translation.end);
+            if coding_start > coding_end {
+                continue;
+            }
+            if (coding_start..=coding_end).contains(&genomic_pos) {
+                cds_offset += if self.strand == Strand::PLUS {
+                    genomic_pos - coding_start
+                } else {
+                    coding_end - genomic_pos
+                };
+                return Some(cds_offset / 3 + 1);
+            }
+            cds_offset += coding_end - coding_start + 1;
+        }
+        None
+    }
+
+    /// The Ensembl `(phase, end_phase)` of each exon, in the same order as [Transcript::exons].
+    /// * The lookup endpoint doesn't report phases, so they are computed from the translation.
+    /// * `phase` is the number of coding bases before the exon modulo 3, and `end_phase` the
+    ///   number through the end of the exon modulo 3.
+    /// * Either is `-1` where that end of the exon is outside the coding sequence, so non-coding
+    ///   exons and transcripts without a translation are `(-1, -1)`.
+    pub fn exon_phases(&self) -> Vec<(i8, i8)> {
+        let Some(translation) = &self.translation else {
+            return vec![(-1, -1); self.exons.len()];
+        };
+        let mut order: Vec<usize> = (0..self.exons.len()).collect();
+        order.sort_by_key(|&i| self.exons[i].start);
+        if self.strand != Strand::PLUS {
+            order.reverse();
+        }
+        let mut phases = vec![(-1, -1); self.exons.len()];
+        let mut cds_offset = 0;
+        for i in order {
+            let exon = &self.exons[i];
+            let coding_start = exon.start.max(translation.start);
+            let coding_end = exon.end.min(translation.end);
+            if coding_start > coding_end {
+                continue;
+            }
+            let (five_prime_coding, three_prime_coding) = if self.strand == Strand::PLUS {
+                (exon.start >= translation.start, exon.end <= translation.end)
+            } else {
+                (exon.end <= translation.end, exon.start >= translation.start)
+            };
+            let phase = (cds_offset % 3) as i8;
+            cds_offset += coding_end - coding_start + 1;
+            phases[i] = (
+                if five_prime_coding { phase } else { -1 },
+                if three_prime_coding {
+                    (cds_offset % 3) as i8
+                } else {
+                    -1
+                },
+            );
+        }
+        phases
+    }
+
+    /// The splice region the genomic range `first..=last` touches, following VEP's windows.
+    /// * [SpliceRegion::Donor] and [SpliceRegion::Acceptor] are the first and last two bases of an intron,
+    ///   in the orientation of the transcript.
+    /// * [SpliceRegion::Region] is within 3 bases of an intron on the exon side, or 3 to 8 bases into it.
+    /// * Returns [None] for ranges away from any exon boundary, or if the transcript has a single exon.
+    pub fn splice_region(&self, first: u32, last: u32) -> Option<SpliceRegion> {
+        let mut exons: Vec<&Exon> = self.exons.iter().collect();
+        exons.sort_by_key(|exon| exon.start);
+        let overlaps = |start: u32, end: u32| first <= end && last >= start;
+        let mut region = None;
+        for (upstream, downstream) in exons.iter().tuple_windows() {
+            let (intron_start, intron_end) = (upstream.end + 1, downstream.start - 1);
+            if intron_start > intron_end {
+                continue;
+            }
+            let (low, high) = if self.strand == Strand::PLUS {
+                (SpliceRegion::Donor, SpliceRegion::Acceptor)
+            } else {
+                (SpliceRegion::Acceptor, SpliceRegion::Donor)
+            };
+            if overlaps(intron_start, (intron_start + 1).min(intron_end)) {
+                return Some(low);
+            }
+            if overlaps(intron_end.saturating_sub(1).max(intron_start), intron_end) {
+                return Some(high);
+            }
+            if overlaps(intron_start.saturating_sub(3), intron_start + 7)
+                || overlaps(intron_end.saturating_sub(7), intron_end + 3)
+            {
+                region = Some(SpliceRegion::Region);
+            }
+        }
+        region
+    }
 }
 
 impl crate::EnsemblPostEndpoint for Transcript {
@@ -69,6 +366,27 @@ impl crate::EnsemblPostEndpoint for Transcript {
     }
 }
 
+/// The strength of a start codon's context, judged on the two positions that matter most.
+/// * `Strong` with a purine (A or G) at -3 and a G at +4, `Adequate` with one of the two, `Weak` with neither.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub enum KozakStrength {
+    Strong,
+    Adequate,
+    Weak,
+}
+impl KozakStrength {
+    /// Classify the context from the bases at -3 (before the A of the ATG) and +4 (after the G).
+    pub fn classify(minus_three: char, plus_four: char) -> Self {
+        let purine = matches!(minus_three.to_ascii_uppercase(), 'A' | 'G');
+        let g = plus_four.eq_ignore_ascii_case(&'G');
+        match (purine, g) {
+            (true, true) => KozakStrength::Strong,
+            (false, false) => KozakStrength::Weak,
+            _ => KozakStrength::Adequate,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
 pub struct Utr {
     pub id: String,
@@ -76,7 +394,7 @@ pub struct Utr {
     pub parent: String,
     pub start: u32,
     pub end: u32,
-    pub strand: i8,
+    pub strand: Strand,
     #[serde(rename = "type")]
     pub utr_type: UtrType,
 }
@@ -93,7 +411,13 @@ pub struct Exon {
     pub id: String,
     pub start: u32,
     pub end: u32,
-    pub strand: i8,
+    pub strand: Strand,
+}
+impl Exon {
+    /// The number of bases in the exon, counting both ends.
+    pub fn length(&self) -> u32 {
+        self.end - self.start + 1
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
@@ -105,21 +429,42 @@ pub struct Translation {
 }
 /// Provide the reverse complementary sequence of a nucleotide sequence
 pub fn reverse_complement(seq: &str) -> String {
-    let mut output = String::new();
-    for b in seq.chars().rev() {
-        output.push(match b {
-            'a' => 't',
-            'A' => 'T',
-            'c' => 'g',
-            'C' => 'G',
-            'g' => 'c',
-            'G' => 'C',
-            't' => 'a',
-            'T' => 'A',
-            _ => panic!("{b} is not a recognized nucletide base"),
-        });
+    reverse_complement_iter(seq).collect()
+}
+
+/// Provide the complementary sequence of a nucleotide sequence, without reversing it.
+pub fn complement(seq: &str) -> String {
+    seq.chars().map(complement_base).collect()
+}
+
+/// Iterate over the reverse complement of a nucleotide sequence without allocating it.
+/// ```
+/// use rs_embl::transcript::reverse_complement_iter;
+/// let mut codons = reverse_complement_iter("ATGGTG").collect::<Vec<_>>();
+/// assert_eq!(codons.drain(..3).collect::<String>(), "CAC");
+/// ```
+pub fn reverse_complement_iter(seq: &str) -> impl Iterator<Item = char> + '_ {
+    seq.chars().rev().map(complement_base)
+}
+
+/// The complement of a single base, preserving case so masked regions stay masked.
+/// # Panics
+///
+/// Panics if `b` is not one of `ACGTN`, in either case.
+fn complement_base(b: char) -> char {
+    match b {
+        'a' => 't',
+        'A' => 'T',
+        'c' => 'g',
+        'C' => 'G',
+        'g' => 'c',
+        'G' => 'C',
+        't' => 'a',
+        'T' => 'A',
+        'n' => 'n',
+        'N' => 'N',
+        _ => panic!("{b} is not a recognized nucletide base"),
     }
-    output
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
@@ -128,6 +473,64 @@ pub struct TranslationConsequence {
     pub stop_index: Option<usize>,
     pub last_ejc_index: Option<usize>,
     pub translation_type: TranslationType,
+    /// Whether the first codon is ATG.
+    #[serde(default)]
+    pub starts_with_atg: bool,
+    /// Whether the first codon is an initiation codon of the genetic code it was translated with,
+    /// including alternative starts such as ATA on the mitochondrial genome, see [GeneticCode::is_start].
+    /// * A false value explains a [TranslationType::NONSTOP] or odd protein by a missing start.
+    #[serde(default)]
+    pub has_start_codon: bool,
+}
+
+/// The protein sequence followed by its [TranslationType], eg `MAS* (NORMAL)`.
+impl std::fmt::Display for TranslationConsequence {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} ({:?})", self.protein_sequence, self.translation_type)
+    }
+}
+
+impl TranslationConsequence {
+    /// The protein sequence in three letter codes, eg `"MetAlaSer...Ter"`.
+    pub fn protein_sequence_three_letter(&self) -> String {
+        self.protein_sequence.chars().map(one_to_three).collect()
+    }
+}
+
+/// The three letter code of a one letter amino acid code, with `*` as `Ter`.
+/// * Unrecognised codes are `Xaa`.
+/// ```
+/// use rs_embl::transcript::one_to_three;
+/// assert_eq!(one_to_three('V'), "Val");
+/// assert_eq!(one_to_three('*'), "Ter");
+/// ```
+pub fn one_to_three(aa: char) -> &'static str {
+    match aa {
+        'A' => "Ala",
+        'C' => "Cys",
+        'D' => "Asp",
+        'E' => "Glu",
+        'F' => "Phe",
+        'G' => "Gly",
+        'H' => "His",
+        'I' => "Ile",
+        'K' => "Lys",
+        'L' => "Leu",
+        'M' => "Met",
+        'N' => "Asn",
+        'P' => "Pro",
+        'Q' => "Gln",
+        'R' => "Arg",
+        'S' => "Ser",
+        'T' => "Thr",
+        'V' => "Val",
+        'W' => "Trp",
+        'Y' => "Tyr",
+        'U' => "Sec",
+        'O' => "Pyl",
+        '*' => "Ter",
+        _ => "Xaa",
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
@@ -139,30 +542,39 @@ pub enum TranslationType {
     ERROR,
 }
 
-pub fn translate(seq: &str) -> TranslationConsequence {
-    let last_ejc_capture = Regex::new(LAST_EJC_REGEX).unwrap().captures(seq);
-    let last_ejc_index = match last_ejc_capture {
-        Some(capture) => Some(capture.get(1).unwrap().start()),
-        None => None,
-    };
-    let mut output = String::new();
-    let mut counter: usize = 0;
-    for codon in seq
-        .chars()
-        .map(|c| {
-            counter += 1;
-            if c == 'T' {
-                return 'U';
-            } else if c == 't' {
-                return 'u';
-            } else {
-                return c;
+/// The codon table used to translate a transcript, numbered as NCBI's translation tables.
+/// * [Transcript::genetic_code] picks the table for a transcript from its sequence region.
+/// ```
+/// use rs_embl::transcript::{translate_with, GeneticCode};
+/// let code: GeneticCode = "2".parse().unwrap();
+/// assert_eq!(code, GeneticCode::VertebrateMitochondrial);
+/// assert_eq!(translate_with("ATGTGAAGA", code).protein_sequence, "MW*");
+/// ```
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub enum GeneticCode {
+    /// Table 1, used by nuclear genes.
+    #[default]
+    Standard,
+    /// Table 2, used by the vertebrate mitochondrial genome: AGA and AGG are stops, ATA is Met
+    /// and TGA is Trp.
+    VertebrateMitochondrial,
+}
+impl GeneticCode {
+    /// The one letter amino acid `codon` encodes, with `*` for a stop, or [None] for a codon
+    /// containing anything other than ACGT.
+    /// * `codon` is upper case, in either DNA or RNA bases.
+    pub fn amino_acid(&self, codon: (char, char, char)) -> Option<char> {
+        let rna = |c: char| if c == 'T' { 'U' } else { c };
+        let codon = (rna(codon.0), rna(codon.1), rna(codon.2));
+        if *self == GeneticCode::VertebrateMitochondrial {
+            match codon {
+                ('A', 'G', 'A') | ('A', 'G', 'G') => return Some('*'),
+                ('A', 'U', 'A') => return Some('M'),
+                ('U', 'G', 'A') => return Some('W'),
+                _ => {}
             }
-        })
-        .filter(|c| c.is_uppercase())
-        .tuples()
-    {
-        let aa = match codon {
+        }
+        Some(match codon {
             ('G', 'C', _) => 'A',
             ('U', 'G', 'U') | ('U', 'G', 'C') => 'C',
             ('G', 'A', 'U') | ('G', 'A', 'C') => 'D',
@@ -184,11 +596,163 @@ pub fn translate(seq: &str) -> TranslationConsequence {
             ('U', 'G', 'G') => 'W',
             ('U', 'A', 'U') | ('U', 'A', 'C') => 'Y',
             ('U', 'A', 'A') | ('U', 'A', 'G') | ('U', 'G', 'A') => '*',
-            _ => panic!("{codon:?} is not a recognized codon"),
+            _ => return None,
+        })
+    }
+
+    /// Whether `codon` can initiate translation, as listed in the NCBI table.
+    /// * The standard code also starts at CTG and TTG, the mitochondrial code at ATT, ATC, ATA and GTG.
+    /// * `codon` is upper case, in either DNA or RNA bases.
+    /// ```
+    /// use rs_embl::transcript::GeneticCode;
+    /// assert!(GeneticCode::Standard.is_start(('A', 'T', 'G')));
+    /// assert!(!GeneticCode::Standard.is_start(('A', 'T', 'A')));
+    /// assert!(GeneticCode::VertebrateMitochondrial.is_start(('A', 'U', 'A')));
+    /// ```
+    pub fn is_start(&self, codon: (char, char, char)) -> bool {
+        let dna = |c: char| if c == 'U' { 'T' } else { c };
+        let codon = (dna(codon.0), dna(codon.1), dna(codon.2));
+        match self {
+            GeneticCode::Standard => {
+                matches!(codon, ('A', 'T', 'G') | ('C', 'T', 'G') | ('T', 'T', 'G'))
+            }
+            GeneticCode::VertebrateMitochondrial => {
+                matches!(codon, ('A', 'T', _) | ('G', 'T', 'G'))
+            }
+        }
+    }
+}
+impl FromStr for GeneticCode {
+    type Err = String;
+    /// Parse an NCBI table number or name, eg `2` or `vertebrate_mitochondrial`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "1" | "standard" => Ok(GeneticCode::Standard),
+            "2" | "vertebrate_mitochondrial" | "mt" => Ok(GeneticCode::VertebrateMitochondrial),
+            _ => Err(format!("`{s}` is not a supported genetic code")),
+        }
+    }
+}
+
+/// The index in `seq` of the last exonic base before the final exon-exon junction, as used by
+/// [translate] to predict nonsense mediated decay.
+/// * `seq` is a genomic sequence with exons upper case and introns lower case, as returned by
+///   [crate::sequence::GenomicSequence].
+/// * Keys on the last upper case base followed by a lower case run and then an upper case run
+///   reaching the end of `seq`, ie `[A-Z][a-z]+[A-Z]+$`, with at least one base before it.
+/// * [None] for a single exon sequence, or one ending in lower case bases.
+/// ```
+/// use rs_embl::transcript::last_ejc_index;
+/// assert_eq!(last_ejc_index("ATGgtaCCCagTAA"), Some(8));
+/// assert_eq!(last_ejc_index("ATGCCCTAA"), None);
+/// assert_eq!(last_ejc_index("ATGgtaCCCTAAaaa"), None);
+/// ```
+pub fn last_ejc_index(seq: &str) -> Option<usize> {
+    Regex::new(LAST_EJC_REGEX)
+        .unwrap()
+        .captures(seq)
+        .map(|capture| capture.get(1).unwrap().start())
+}
+
+/// Translate the exonic (uppercase) bases of `seq` up to the first stop codon, with the standard code.
+/// # Panics
+///
+/// Panics on a codon containing anything other than ACGT before the first stop, eg an N.
+pub fn translate(seq: &str) -> TranslationConsequence {
+    translate_with(seq, GeneticCode::Standard)
+}
+
+/// [translate] each of `seqs`, returning the translations in the same order.
+/// * With the `rayon` feature the sequences are translated in parallel, which pays off for thousands
+///   of edited sequences, eg saturation mutagenesis. Serial without it.
+/// # Panics
+///
+/// As [translate], for any of `seqs`.
+pub fn translate_many(seqs: &[String]) -> Vec<TranslationConsequence> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        seqs.par_iter().map(|seq| translate(seq)).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        seqs.iter().map(|seq| translate(seq)).collect()
+    }
+}
+
+/// As [translate], with the codon table `code`.
+/// # Panics
+///
+/// As [translate].
+pub fn translate_with(seq: &str, code: GeneticCode) -> TranslationConsequence {
+    try_translate(seq, code).unwrap_or_else(|codon| panic!("{codon} is not a recognized codon"))
+}
+
+/// As [translate_with], for an already spliced `seq` with the offsets of its exon-exon junctions,
+/// eg from [GenomicSequence::spliced_with_junctions].
+/// * Nonsense mediated decay is predicted from the last of `junctions`, rather than recovered from
+///   the case of `seq` by [last_ejc_index], so it holds for sequences without their introns.
+/// * [TranslationConsequence::stop_index] and [TranslationConsequence::last_ejc_index] are offsets
+///   into `seq`, so the 50 base rule is measured on the spliced transcript.
+/// ```
+/// use rs_embl::transcript::{translate_spliced, GeneticCode, TranslationType};
+/// let translation = translate_spliced(&format!("ATGTAA{}", "C".repeat(60)), &[60], GeneticCode::Standard);
+/// assert_eq!(translation.last_ejc_index, Some(59));
+/// assert_eq!(translation.translation_type, TranslationType::NMD);
+/// ```
+/// # Panics
+///
+/// As [translate].
+pub fn translate_spliced(
+    seq: &str,
+    junctions: &[usize],
+    code: GeneticCode,
+) -> TranslationConsequence {
+    let last_ejc_index = junctions
+        .last()
+        .and_then(|junction| junction.checked_sub(1));
+    try_translate_with_ejc(seq, code, last_ejc_index)
+        .unwrap_or_else(|codon| panic!("{codon} is not a recognized codon"))
+}
+
+/// As [translate_with], but returns the first unrecognized codon as an error instead of panicking.
+fn try_translate(seq: &str, code: GeneticCode) -> Result<TranslationConsequence, String> {
+    try_translate_with_ejc(seq, code, last_ejc_index(seq))
+}
+
+/// As [try_translate], with the last exon-exon junction given rather than recovered from `seq`.
+fn try_translate_with_ejc(
+    seq: &str,
+    code: GeneticCode,
+    last_ejc_index: Option<usize>,
+) -> Result<TranslationConsequence, String> {
+    let first_codon = seq.chars().filter(|c| c.is_uppercase()).next_tuple();
+    let starts_with_atg = first_codon == Some(('A', 'T', 'G'));
+    let has_start_codon = first_codon.is_some_and(|codon| code.is_start(codon));
+    let mut output = String::new();
+    let mut counter: usize = 0;
+    for codon in seq
+        .chars()
+        .map(|c| {
+            counter += 1;
+            if c == 'T' {
+                'U'
+            } else if c == 't' {
+                'u'
+            } else {
+                c
+            }
+        })
+        .filter(|c| c.is_uppercase())
+        .tuples()
+    {
+        let Some(aa) = code.amino_acid(codon) else {
+            let (a, b, c) = codon;
+            return Err(format!("{a}{b}{c}").replace('U', "T"));
         };
         output.push(aa);
         if aa == '*' {
-            return TranslationConsequence {
+            return Ok(TranslationConsequence {
                 protein_sequence: output,
                 stop_index: Some(counter),
                 last_ejc_index,
@@ -202,29 +766,89 @@ pub fn translate(seq: &str) -> TranslationConsequence {
                         }
                     }
                 },
-            };
+                starts_with_atg,
+                has_start_codon,
+            });
         }
     }
-    TranslationConsequence {
+    Ok(TranslationConsequence {
         protein_sequence: output,
         stop_index: None,
         last_ejc_index,
         translation_type: TranslationType::NONSTOP,
-    }
+        starts_with_atg,
+        has_start_codon,
+    })
 }
 
+/// Apply `variant_allele` in place of `start..=end` and translate the edited transcript.
+/// * Variants crossing an exon boundary are a [Consequences::DisruptedSpliceSite], unless they delete
+///   whole exons from intron to intron, which are skipped in the edited transcript.
+/// * Intronic variants are [Consequences::Splicing] within the splice sites or region, otherwise
+///   [Consequences::Intron].
+/// * Variants touching an ATG start codon are a [Consequences::LostStart] if it no longer reads ATG,
+///   otherwise [Consequences::StartRetained].
+/// * Exonic variants in a transcript without a translation, eg a lncRNA, are a
+///   [Consequences::NonCodingExon].
+/// * `seq` is the unflanked [GenomicSequence] of `transcript`. `variant_allele` is on the plus strand,
+///   with `-` or an empty string for a deletion.
+/// # Errors
+///
+/// Returns a [ConsequenceError] if the inputs are inconsistent, or a codon to be translated contains
+/// an ambiguous base.
 pub fn make_consequences(
     seq: &GenomicSequence,
     transcript: &Transcript,
     start: u32,
     end: u32,
     variant_allele: &str,
-) -> Consequences {
+) -> Result<Consequences, ConsequenceError> {
     let mut edited_sequence: String = String::default();
     let upstream;
     let downstream;
 
-    if transcript.strand == 1 {
+    // Insertions are reported with the start one past the end.
+    let (first, last) = (start.min(end), start.max(end));
+    // The bases either side of the variant are needed to tell exons from introns.
+    if first <= transcript.start || last >= transcript.end {
+        return Err(ConsequenceError::OutsideTranscript { start, end });
+    }
+    let expected = (transcript.end - transcript.start + 1) as usize;
+    if seq.seq.len() != expected {
+        return Err(ConsequenceError::SequenceLength {
+            expected,
+            actual: seq.seq.len(),
+        });
+    }
+    let variant_allele = if variant_allele == "-" {
+        ""
+    } else {
+        variant_allele
+    };
+    if !variant_allele
+        .chars()
+        .all(|c| matches!(c, 'A' | 'C' | 'G' | 'T' | 'N' | 'a' | 'c' | 'g' | 't' | 'n'))
+    {
+        return Err(ConsequenceError::InvalidAllele(variant_allele.to_owned()));
+    }
+    let overlapping: Vec<&Exon> = transcript
+        .exons
+        .iter()
+        .filter(|exon| exon.start <= last && exon.end >= first)
+        .collect();
+    let within_exon = overlapping
+        .iter()
+        .any(|exon| exon.start <= first && last <= exon.end);
+    // Deleting whole exons, along with their splice sites, skips them rather than disrupting splicing.
+    let spans_exons = !overlapping.is_empty()
+        && overlapping
+            .iter()
+            .all(|exon| first < exon.start && exon.end < last);
+    if !overlapping.is_empty() && !within_exon && !spans_exons {
+        return Ok(Consequences::DisruptedSpliceSite);
+    }
+
+    if transcript.strand == Strand::PLUS {
         upstream = &seq.seq[..(start - transcript.start) as usize];
         downstream = &seq.seq[(end - transcript.start + 1) as usize..];
     } else {
@@ -235,43 +859,106 @@ pub fn make_consequences(
         downstream.chars().next().unwrap().is_lowercase(),
         upstream.chars().last().unwrap().is_lowercase(),
     ) {
-        (true, true) => return Consequences::Intron,
+        (true, true) if spans_exons => {}
+        (true, true) => {
+            return Ok(match transcript.splice_region(first, last) {
+                Some(region) => Consequences::Splicing(region),
+                None => Consequences::Intron,
+            })
+        }
         (true, false) | (false, true) => {
-            return Consequences::DisruptedSpliceSite;
+            return Ok(Consequences::DisruptedSpliceSite);
         }
         (false, false) => {}
     }
     edited_sequence.push_str(upstream);
-    if transcript.strand == 1 {
+    if transcript.strand == Strand::PLUS {
         edited_sequence.push_str(variant_allele);
     } else {
         edited_sequence.push_str(&reverse_complement(variant_allele));
     }
     edited_sequence.push_str(downstream);
 
-    let mut edited_protein_sequence = TranslationConsequence::default();
-    let mut unedited_protein_sequence = TranslationConsequence::default();
-    if let Some(translation) = &transcript.translation {
-        edited_protein_sequence = translate(
-            &edited_sequence[if transcript.strand == 1 {
-                (translation.start - transcript.start) as usize
-            } else {
-                (transcript.end - translation.end) as usize
-            }..],
-        );
-        unedited_protein_sequence = translate(
-            &seq.seq[if transcript.strand == 1 {
-                (translation.start - transcript.start) as usize
-            } else {
-                (transcript.end - translation.end) as usize
-            }..],
-        );
+    let Some(translation) = &transcript.translation else {
+        return Ok(Consequences::NonCodingExon {
+            edited_exonic_sequence: splice(&edited_sequence).0,
+        });
+    };
+    let cds_start = transcript
+        .cds_offset_in_genomic()
+        .filter(|cds_start| *cds_start < seq.seq.len().min(edited_sequence.len()))
+        .ok_or(ConsequenceError::InvalidTranslation)?;
+    let start_codon = if transcript.strand == Strand::PLUS {
+        translation.start..=translation.start + 2
+    } else {
+        translation.end - 2..=translation.end
+    };
+    let mut start_codon_hit = false;
+    // Only a variant on a confirmed ATG can lose it.
+    if first <= *start_codon.end()
+        && last >= *start_codon.start()
+        && first_codon(&seq.seq[cds_start..]) == "ATG"
+    {
+        if first_codon(&edited_sequence[cds_start..]) != "ATG" {
+            return Ok(Consequences::LostStart);
+        }
+        start_codon_hit = true;
+    }
+    let code = transcript.genetic_code();
+    let edited_protein_sequence = try_translate(&edited_sequence[cds_start..], code)
+        .map_err(ConsequenceError::UnrecognizedCodon)?;
+    let unedited_protein_sequence =
+        try_translate(&seq.seq[cds_start..], code).map_err(ConsequenceError::UnrecognizedCodon)?;
+    // An insertion lies between `first` and `last`, so must fall strictly inside the CDS.
+    let in_cds = if start > end {
+        translation.start <= first && last <= translation.end
+    } else {
+        first <= translation.end && last >= translation.start
+    };
+    let exonic_bases = |seq: &str| seq.chars().filter(|c| c.is_uppercase()).count() as i64;
+    let length_change = exonic_bases(&edited_sequence) - exonic_bases(&seq.seq);
+    let frameshift = (in_cds && length_change % 3 != 0).then(|| Frameshift {
+        length_change,
+        new_stop_distance: new_stop_distance(
+            unedited_protein_sequence.protein_sequence.as_bytes(),
+            edited_protein_sequence.protein_sequence.as_bytes(),
+        ),
+    });
+    if start_codon_hit {
+        return Ok(Consequences::StartRetained {
+            edited_genomic_sequence: edited_sequence,
+            edited_protein_sequence,
+            unedited_protein_sequence,
+            length_change,
+        });
     }
-    Consequences::Coding {
+    Ok(Consequences::Coding {
         edited_genomic_sequence: edited_sequence,
         edited_protein_sequence,
         unedited_protein_sequence,
-    }
+        length_change,
+        frameshift,
+    })
+}
+
+/// Why [make_consequences] couldn't compute the consequences of a variant.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ConsequenceError {
+    #[error("The variant at {start}-{end} is not strictly within the transcript")]
+    OutsideTranscript { start: u32, end: u32 },
+    #[error("Expected a genomic sequence of {expected} bases for the transcript, got {actual}")]
+    SequenceLength { expected: usize, actual: usize },
+    #[error("`{0}` is not a nucleotide sequence")]
+    InvalidAllele(String),
+    #[error("The translation does not start within the transcript")]
+    InvalidTranslation,
+    #[error("{0} is not a recognized codon")]
+    UnrecognizedCodon(String),
+}
+
+/// The first three exonic (uppercase) bases of a masked sequence.
+fn first_codon(seq: &str) -> String {
+    seq.chars().filter(|c| c.is_uppercase()).take(3).collect()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
@@ -281,8 +968,298 @@ pub enum Consequences {
         edited_genomic_sequence: String,
         edited_protein_sequence: TranslationConsequence,
         unedited_protein_sequence: TranslationConsequence,
+        /// The net number of exonic bases inserted, negative for a deletion, which decides whether
+        /// the [ProteinChange] is in frame.
+        #[serde(default)]
+        length_change: i64,
+        /// Set when the variant changes the coding length by other than a multiple of three, VEP's
+        /// `frameshift_variant`.
+        #[serde(default)]
+        frameshift: Option<Frameshift>,
     },
     Intron,
+    /// The variant is in an intron, near enough an exon to be in a splice site or region, see
+    /// [Transcript::splice_region].
+    Splicing(SpliceRegion),
+    /// The variant changes an ATG start codon to something else, VEP's `start_lost`.
+    LostStart,
+    /// The variant touches the start codon but leaves it ATG, VEP's `start_retained_variant`.
+    StartRetained {
+        edited_genomic_sequence: String,
+        edited_protein_sequence: TranslationConsequence,
+        unedited_protein_sequence: TranslationConsequence,
+        /// As for [Consequences::Coding].
+        #[serde(default)]
+        length_change: i64,
+    },
+    /// The variant is in an exon of a transcript without a translation, eg a lncRNA, VEP's
+    /// `non_coding_transcript_exon_variant`.
+    NonCodingExon {
+        /// The spliced exons of the edited transcript.
+        edited_exonic_sequence: String,
+    },
+}
+
+/// A change to the reading frame of a [Consequences::Coding] variant.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct Frameshift {
+    /// The net number of coding bases inserted, negative for a deletion, eg -1 for a single base deletion.
+    pub length_change: i64,
+    /// The residues from the first changed one to the new stop codon, counting the stop, as in `fsTer12`.
+    /// * [None] if the shifted frame reads to the end of the transcript without a stop.
+    pub new_stop_distance: Option<usize>,
+}
+
+/// Where a variant lies relative to the nearest exon boundary, see [Transcript::splice_region].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub enum SpliceRegion {
+    /// The first two bases of an intron, VEP's `splice_donor_variant`.
+    Donor,
+    /// The last two bases of an intron, VEP's `splice_acceptor_variant`.
+    Acceptor,
+    /// Near an exon boundary but outside the donor and acceptor sites, VEP's `splice_region_variant`.
+    Region,
+}
+impl SpliceRegion {
+    pub fn consequence(&self) -> crate::Consequence {
+        match self {
+            SpliceRegion::Donor => crate::Consequence::splice_donor_variant,
+            SpliceRegion::Acceptor => crate::Consequence::splice_acceptor_variant,
+            SpliceRegion::Region => crate::Consequence::splice_region_variant,
+        }
+    }
+}
+
+impl Consequences {
+    /// The change to the protein, or [None] unless the variant is [Consequences::Coding] or
+    /// [Consequences::StartRetained].
+    pub fn protein_change(&self) -> Option<ProteinChange> {
+        match self {
+            Consequences::Coding {
+                edited_protein_sequence,
+                unedited_protein_sequence,
+                length_change,
+                ..
+            }
+            | Consequences::StartRetained {
+                edited_protein_sequence,
+                unedited_protein_sequence,
+                length_change,
+                ..
+            } => Some(ProteinChange::between(
+                unedited_protein_sequence,
+                edited_protein_sequence,
+                *length_change,
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// The first amino acid that differs between an unedited and edited protein.
+/// * `pos` is 1-based. For a [ProteinChangeKind::Silent] change it is 0 and `from` and `to` are `'='`.
+/// * `to` is `'-'` for residues deleted from the end of the protein.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct ProteinChange {
+    pub pos: usize,
+    pub from: char,
+    pub to: char,
+    pub kind: ProteinChangeKind,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub enum ProteinChangeKind {
+    Silent,
+    Missense,
+    /// A premature stop codon.
+    Nonsense,
+    /// The stop codon is replaced, extending the protein.
+    StopLost,
+    /// Residues inserted or deleted without changing the reading frame.
+    InFrameIndel,
+    Frameshift,
+}
+
+impl ProteinChange {
+    /// Compare two translations of the same transcript, edited by `length_change` bases.
+    /// * A change reaching a stop codon is [ProteinChangeKind::Nonsense], unless it only deleted the
+    ///   residues before the original stop.
+    /// * Otherwise the change is a frameshift unless `length_change` is a multiple of three.
+    pub fn between(
+        unedited: &TranslationConsequence,
+        edited: &TranslationConsequence,
+        length_change: i64,
+    ) -> Self {
+        let unedited = unedited.protein_sequence.as_bytes();
+        let edited = edited.protein_sequence.as_bytes();
+        let Some((prefix, suffix)) = diff_bounds(unedited, edited) else {
+            return Self {
+                pos: 0,
+                from: '=',
+                to: '=',
+                kind: ProteinChangeKind::Silent,
+            };
+        };
+        let from = unedited.get(prefix).map_or('-', |&aa| aa as char);
+        let to = edited.get(prefix).map_or('-', |&aa| aa as char);
+        let changed_unedited = unedited.len() - prefix - suffix;
+        let changed_edited = edited.len() - prefix - suffix;
+        let deleted = changed_edited == 0 && 3 * changed_unedited as i64 == -length_change;
+        let kind = if from == '*' {
+            ProteinChangeKind::StopLost
+        } else if to == '*' && !deleted {
+            ProteinChangeKind::Nonsense
+        } else if length_change % 3 != 0 {
+            ProteinChangeKind::Frameshift
+        } else if changed_unedited == changed_edited {
+            ProteinChangeKind::Missense
+        } else {
+            ProteinChangeKind::InFrameIndel
+        };
+        Self {
+            pos: prefix + 1,
+            from,
+            to,
+            kind,
+        }
+    }
+}
+
+/// The lengths of the common prefix and suffix of two proteins, or [None] if they are identical.
+/// * The prefix is taken first, so changes within repeats are placed as far C-terminal as possible.
+fn diff_bounds(unedited: &[u8], edited: &[u8]) -> Option<(usize, usize)> {
+    let prefix = unedited
+        .iter()
+        .zip(edited.iter())
+        .take_while(|(u, e)| u == e)
+        .count();
+    if prefix == unedited.len() && prefix == edited.len() {
+        return None;
+    }
+    let max_suffix = unedited.len().min(edited.len()) - prefix;
+    let suffix = unedited
+        .iter()
+        .rev()
+        .zip(edited.iter().rev())
+        .take(max_suffix)
+        .take_while(|(u, e)| u == e)
+        .count();
+    Some((prefix, suffix))
+}
+
+/// The residues of `edited` from the first one differing from `unedited` up to its stop codon,
+/// counting the stop, or [None] if they don't differ or there is no stop.
+fn new_stop_distance(unedited: &[u8], edited: &[u8]) -> Option<usize> {
+    let (prefix, _) = diff_bounds(unedited, edited)?;
+    edited[prefix.min(edited.len())..]
+        .iter()
+        .position(|&c| c == b'*')
+        .map(|n| n + 1)
+}
+
+/// HGVS protein notation for the change between two translations of the same transcript,
+/// eg `p.Val50Met`, `p.Arg97Ter`, `p.Lys50ArgfsTer12` or `p.(=)`.
+/// * Frameshifts and stop losses count the residues up to the new stop codon, or use `Ter?` if there isn't one.
+/// * In frame insertions are written as `ins` rather than checked for duplications.
+/// * `length_change` is the net number of bases inserted, as for [ProteinChange::between].
+pub fn hgvs_protein(
+    unedited: &TranslationConsequence,
+    edited: &TranslationConsequence,
+    length_change: i64,
+) -> String {
+    let change = ProteinChange::between(unedited, edited, length_change);
+    let unedited = unedited.protein_sequence.as_bytes();
+    let edited = edited.protein_sequence.as_bytes();
+    let Some((prefix, suffix)) = diff_bounds(unedited, edited) else {
+        return "p.(=)".to_owned();
+    };
+    let aa = |seq: &[u8], i: usize| one_to_three(seq[i] as char);
+    let residues = |seq: &[u8]| {
+        seq.iter()
+            .map(|&c| one_to_three(c as char))
+            .collect::<String>()
+    };
+    let pos = prefix + 1;
+    let from = unedited
+        .get(prefix)
+        .map_or("", |&c| one_to_three(c as char));
+    let to = edited.get(prefix).map_or("", |&c| one_to_three(c as char));
+    let new_stop = || new_stop_distance(unedited, edited).map_or("?".to_owned(), |n| n.to_string());
+    let changed_unedited = unedited.len() - prefix - suffix;
+    let changed_edited = edited.len() - prefix - suffix;
+    match change.kind {
+        ProteinChangeKind::Silent => "p.(=)".to_owned(),
+        ProteinChangeKind::Nonsense => format!("p.{from}{pos}Ter"),
+        ProteinChangeKind::StopLost => format!("p.Ter{pos}{to}extTer{}", new_stop()),
+        ProteinChangeKind::Frameshift if to.is_empty() => format!("p.{from}{pos}fs"),
+        ProteinChangeKind::Frameshift => format!("p.{from}{pos}{to}fsTer{}", new_stop()),
+        ProteinChangeKind::Missense if changed_unedited == 1 => format!("p.{from}{pos}{to}"),
+        ProteinChangeKind::InFrameIndel if changed_unedited == 0 => format!(
+            "p.{}{prefix}_{from}{pos}ins{}",
+            aa(unedited, prefix - 1),
+            residues(&edited[prefix..prefix + changed_edited])
+        ),
+        ProteinChangeKind::Missense | ProteinChangeKind::InFrameIndel => {
+            let last = prefix + changed_unedited;
+            let range = if changed_unedited == 1 {
+                format!("{from}{pos}")
+            } else {
+                format!("{from}{pos}_{}{last}", aa(unedited, last - 1))
+            };
+            if changed_edited == 0 {
+                format!("p.{range}del")
+            } else {
+                let inserted = residues(&edited[prefix..prefix + changed_edited]);
+                format!("p.{range}delins{inserted}")
+            }
+        }
+    }
+}
+
+/// A short label, with the [Consequences::hgvs_protein] change for coding variants, eg
+/// `coding p.Val50Met` or `splice donor`.
+impl std::fmt::Display for Consequences {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let hgvs = self.hgvs_protein().unwrap_or_default();
+        match self {
+            Consequences::DisruptedSpliceSite => write!(f, "disrupted splice site"),
+            Consequences::Coding { .. } => write!(f, "coding {hgvs}"),
+            Consequences::Intron => write!(f, "intron"),
+            Consequences::Splicing(SpliceRegion::Donor) => write!(f, "splice donor"),
+            Consequences::Splicing(SpliceRegion::Acceptor) => write!(f, "splice acceptor"),
+            Consequences::Splicing(SpliceRegion::Region) => write!(f, "splice region"),
+            Consequences::LostStart => write!(f, "start lost {hgvs}"),
+            Consequences::StartRetained { .. } => write!(f, "start retained {hgvs}"),
+            Consequences::NonCodingExon { .. } => write!(f, "non-coding exon"),
+        }
+    }
+}
+
+impl Consequences {
+    /// HGVS protein notation for a [Consequences::Coding] variant, see [hgvs_protein].
+    /// * A [Consequences::LostStart] is `p.Met1?`, as its effect on the protein can't be predicted.
+    pub fn hgvs_protein(&self) -> Option<String> {
+        match self {
+            Consequences::Coding {
+                edited_protein_sequence,
+                unedited_protein_sequence,
+                length_change,
+                ..
+            }
+            | Consequences::StartRetained {
+                edited_protein_sequence,
+                unedited_protein_sequence,
+                length_change,
+                ..
+            } => Some(hgvs_protein(
+                unedited_protein_sequence,
+                edited_protein_sequence,
+                *length_change,
+            )),
+            Consequences::LostStart => Some("p.Met1?".to_owned()),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -293,6 +1270,17 @@ mod tests {
     const TTR_201_JSON: &str = r#"{"end":31598821,"object_type":"Transcript","is_canonical":1,"length":616,"db_type":"core","id":"ENST00000237014","Translation":{"version":4,"species":"homo_sapiens","start":31591903,"length":147,"id":"ENSP00000237014","db_type":"core","Parent":"ENST00000237014","end":31598675,"object_type":"Translation"},"species":"homo_sapiens","display_name":"TTR-201","start":31591877,"version":8,"seq_region_name":"18","assembly_name":"GRCh38","logic_name":"ensembl_havana_transcript_homo_sapiens","Exon":[{"species":"homo_sapiens","start":31591877,"version":2,"assembly_name":"GRCh38","seq_region_name":"18","end":31591971,"object_type":"Exon","db_type":"core","id":"ENSE00001836564","strand":1},{"start":31592896,"species":"homo_sapiens","seq_region_name":"18","assembly_name":"GRCh38","version":1,"end":31593026,"object_type":"Exon","id":"ENSE00003556666","db_type":"core","strand":1},{"id":"ENSE00000796939","db_type":"core","strand":1,"end":31595255,"object_type":"Exon","version":1,"seq_region_name":"18","assembly_name":"GRCh38","species":"homo_sapiens","start":31595120},{"end":31598821,"object_type":"Exon","db_type":"core","id":"ENSE00001827041","strand":1,"start":31598568,"species":"homo_sapiens","seq_region_name":"18","assembly_name":"GRCh38","version":2}],"strand":1,"Parent":"ENSG00000118271","source":"ensembl_havana","UTR":[{"assembly_name":"GRCh38","seq_region_name":"18","start":31591877,"source":"ensembl_havana","type":"five_prime_utr","species":"homo_sapiens","db_type":"core","id":"ENST00000237014","strand":1,"Parent":"ENST00000237014","end":31591902,"object_type":"five_prime_UTR"},{"type":"three_prime_utr","species":"homo_sapiens","source":"ensembl_havana","start":31598676,"seq_region_name":"18","assembly_name":"GRCh38","object_type":"three_prime_UTR","end":31598821,"strand":1,"Parent":"ENST00000237014","id":"ENST00000237014","db_type":"core"}],"biotype":"protein_coding"}"#;
     const TTR_V30M_DEL_PROTEIN: &str = "MASHRLLLLCLAGLVFVSEAGPTGTGESKCPLMVKVLDAVRGSPAINVACMCSERLLMTPGSHLPLGKPVSLESCMGSQLRRNL*";
     const TTR_V30M_INS_PROTEIN: &str = "MASHRLLLLCLAGLVFVSEAGPTGTGESKCPLMVKVLDAVRGSPAINVAGACVQKGC*";
+    /// TTR-201 and its genomic sequence, as most tests start from.
+    fn ttr() -> (super::Transcript, GenomicSequence){
+        let transcript = serde_json::from_str::<super::Transcript>(TTR_201_JSON).unwrap();
+        let genomic_seq = GenomicSequence{
+            query: "".to_owned(),
+            id: "".to_owned(),
+            desc: None,
+            seq: TTR_GENOME_SEQ.to_owned(),
+        };
+        (transcript, genomic_seq)
+    }
     #[test]
     fn test_snp(){
         let transcript = serde_json::from_str::<super::Transcript>(TTR_201_JSON).unwrap();
@@ -310,13 +1298,17 @@ mod tests {
             &transcript, 
             START, 
             END, 
-            VARIANT_ALLELE);
+            VARIANT_ALLELE).unwrap();
         let super::Consequences::Coding{
-            edited_genomic_sequence,
             edited_protein_sequence,
-            unedited_protein_sequence,} = consequences else {panic!()};
+            ..} = &consequences else {panic!()};
         const V30M_TTR: &str = "MASHRLLLLCLAGLVFVSEAGPTGTGESKCPLMVKVLDAVRGSPAINVAMHVFRKAADDTWEPFASGKTSESGELHGLTTEEEFVEGIYKVEIDTKSYWKALGISPFHEHAEVVFTANDSGPRRYTIAALLSPYSYSTTAVVTNPKE*";
         assert_eq!(&edited_protein_sequence.protein_sequence, V30M_TTR);
+        let change = consequences.protein_change().unwrap();
+        assert_eq!((change.pos, change.from, change.to), (50, 'V', 'M'));
+        assert_eq!(change.kind, super::ProteinChangeKind::Missense);
+        assert_eq!(consequences.hgvs_protein().unwrap(), "p.Val50Met");
+        assert!(matches!(consequences, super::Consequences::Coding{frameshift: None, ..}));
     }
     #[test]
     fn test_del(){
@@ -335,12 +1327,20 @@ mod tests {
             &transcript, 
             START, 
             END, 
-            VARIANT_ALLELE);
+            VARIANT_ALLELE).unwrap();
         let super::Consequences::Coding{
-            edited_genomic_sequence,
             edited_protein_sequence,
-            unedited_protein_sequence,} = consequences else {panic!()};
+            ..} = &consequences else {panic!()};
         assert_eq!(&edited_protein_sequence.protein_sequence, TTR_V30M_DEL_PROTEIN);
+        let change = consequences.protein_change().unwrap();
+        assert_eq!((change.pos, change.from, change.to), (50, 'V', 'C'));
+        assert_eq!(change.kind, super::ProteinChangeKind::Frameshift);
+        let super::Consequences::Coding{frameshift, ..} = &consequences else {panic!()};
+        assert_eq!(frameshift, &Some(super::Frameshift{length_change: -1, new_stop_distance: Some(TTR_V30M_DEL_PROTEIN.len() - 49)}));
+        assert!(consequences.hgvs_protein().unwrap().ends_with(&format!("fsTer{}", TTR_V30M_DEL_PROTEIN.len() - 49)));
+        // Deleting a whole codon keeps the frame.
+        let in_frame = super::make_consequences(&genomic_seq, &transcript, START, END + 2, VARIANT_ALLELE).unwrap();
+        assert!(matches!(in_frame, super::Consequences::Coding{frameshift: None, ..}));
     }
     #[test]
     fn test_ins(){
@@ -359,11 +1359,358 @@ mod tests {
             &transcript, 
             START, 
             END, 
-            VARIANT_ALLELE);
+            VARIANT_ALLELE).unwrap();
         let super::Consequences::Coding{
-            edited_genomic_sequence,
             edited_protein_sequence,
-            unedited_protein_sequence,} = consequences else {panic!()};
+            frameshift,
+            ..} = consequences else {panic!()};
         assert_eq!(&edited_protein_sequence.protein_sequence, TTR_V30M_INS_PROTEIN);
+        assert_eq!(frameshift.unwrap().length_change, 1);
+    }
+    #[test]
+    fn test_multi_exon_del(){
+        let (transcript, genomic_seq) = ttr();
+        // From intron 1 to intron 2, deleting all of exon 2.
+        let consequences = super::make_consequences(&genomic_seq, &transcript, 31592800, 31593100, "").unwrap();
+        let change = consequences.protein_change().unwrap();
+        assert_eq!(change.pos, 24);
+        assert_eq!(change.kind, super::ProteinChangeKind::Frameshift);
+        let super::Consequences::Coding{edited_genomic_sequence, ..} = consequences else {panic!()};
+        assert_eq!(edited_genomic_sequence.len(), TTR_GENOME_SEQ.len() - 301);
+        // From exon 2 to exon 3, across intron 2.
+        let consequences = super::make_consequences(&genomic_seq, &transcript, 31593000, 31595200, "").unwrap();
+        assert_eq!(consequences, super::Consequences::DisruptedSpliceSite);
+        // From intron 1 into exon 2.
+        let consequences = super::make_consequences(&genomic_seq, &transcript, 31592850, 31592900, "").unwrap();
+        assert_eq!(consequences, super::Consequences::DisruptedSpliceSite);
+    }
+    #[test]
+    fn test_consequence_errors(){
+        let (transcript, genomic_seq) = ttr();
+        let make = |seq: &GenomicSequence, transcript: &super::Transcript, start, end, allele| super::make_consequences(seq, transcript, start, end, allele);
+        assert_eq!(make(&genomic_seq, &transcript, 31591800, 31591800, "A"), Err(super::ConsequenceError::OutsideTranscript{start: 31591800, end: 31591800}));
+        assert!(matches!(make(&genomic_seq, &transcript, 31598821, 31598821, "A"), Err(super::ConsequenceError::OutsideTranscript{..})));
+        let flanked = GenomicSequence{seq: format!("aaa{TTR_GENOME_SEQ}"), ..genomic_seq.clone()};
+        assert_eq!(make(&flanked, &transcript, 31592974, 31592974, "A"), Err(super::ConsequenceError::SequenceLength{expected: 6945, actual: 6948}));
+        assert_eq!(make(&genomic_seq, &transcript, 31592974, 31592974, "R"), Err(super::ConsequenceError::InvalidAllele("R".to_owned())));
+        assert_eq!(make(&genomic_seq, &transcript, 31592974, 31592974, "N"), Err(super::ConsequenceError::UnrecognizedCodon("NTG".to_owned())));
+        let mut outside = transcript.clone();
+        outside.translation.as_mut().unwrap().start = 31591800;
+        assert_eq!(make(&genomic_seq, &outside, 31592974, 31592974, "A"), Err(super::ConsequenceError::InvalidTranslation));
+        // An ambiguous base after the stop codon is never translated.
+        assert!(make(&genomic_seq, &transcript, 31598700, 31598700, "N").is_ok());
+    }
+    #[test]
+    fn test_serde_round_trip(){
+        let (transcript, genomic_seq) = ttr();
+        let json = serde_json::to_string(&transcript).unwrap();
+        assert_eq!(serde_json::from_str::<super::Transcript>(&json).unwrap(), transcript);
+        let json = serde_json::to_string(&genomic_seq).unwrap();
+        assert_eq!(serde_json::from_str::<GenomicSequence>(&json).unwrap(), genomic_seq);
+        for (start, end, allele) in [(31592974, 31592974, "A"), (31591973, 31591973, "A"), (31592200, 31592200, "A"), (31591911, 31591911, "C")] {
+            let consequences = super::make_consequences(&genomic_seq, &transcript, start, end, allele).unwrap();
+            let json = serde_json::to_string(&consequences).unwrap();
+            assert_eq!(serde_json::from_str::<super::Consequences>(&json).unwrap(), consequences, "{json}");
+        }
+        let translation = transcript.translate(&genomic_seq);
+        let json = serde_json::to_string(&translation).unwrap();
+        assert_eq!(serde_json::from_str::<super::TranslationConsequence>(&json).unwrap(), translation);
+    }
+    #[test]
+    fn test_splice_region(){
+        let (transcript, genomic_seq) = ttr();
+        use super::SpliceRegion::*;
+        // Intron 1 is 31591972-31592895.
+        let cases = [(31591969, Some(Region)), (31591972, Some(Donor)), (31591973, Some(Donor)), (31591974, Some(Region)),
+            (31591979, Some(Region)), (31591980, None), (31592200, None), (31592888, Some(Region)), (31592894, Some(Acceptor)),
+            (31592895, Some(Acceptor)), (31592898, Some(Region)), (31592899, None)];
+        for (pos, region) in cases {
+            assert_eq!(transcript.splice_region(pos, pos), region, "{pos}");
+        }
+        let donor = super::make_consequences(&genomic_seq, &transcript, 31591973, 31591973, "A").unwrap();
+        assert_eq!(donor, super::Consequences::Splicing(Donor));
+        let intron = super::make_consequences(&genomic_seq, &transcript, 31592200, 31592200, "A").unwrap();
+        assert_eq!(intron, super::Consequences::Intron);
+        // The same intron on the minus strand swaps donor and acceptor.
+        let minus = super::Transcript{strand: crate::Strand::MINUS, ..transcript};
+        assert_eq!(minus.splice_region(31591972, 31591972), Some(Acceptor));
+        assert_eq!(minus.splice_region(31592895, 31592895), Some(Donor));
+        assert_eq!(Donor.consequence(), crate::Consequence::splice_donor_variant);
+    }
+    #[test]
+    fn test_genetic_code(){
+        use super::GeneticCode::*;
+        // MT-ND1 begins ATA ACC ATG, and MT-CO1 has an internal TGA.
+        assert_eq!(super::translate_with("ATAACCATGTGAAGG", VertebrateMitochondrial).protein_sequence, "MTMW*");
+        assert_eq!(super::translate_with("ATAACCATGTGAAGG", Standard).protein_sequence, "ITM*");
+        assert_eq!(VertebrateMitochondrial.amino_acid(('A', 'G', 'A')), Some('*'));
+        assert_eq!(Standard.amino_acid(('A', 'N', 'A')), None);
+        assert_eq!("vertebrate_mitochondrial".parse(), Ok(VertebrateMitochondrial));
+        assert!("11".parse::<super::GeneticCode>().is_err());
+        let (transcript, genomic_seq) = ttr();
+        assert_eq!(transcript.seq_region_name, "18");
+        assert_eq!(transcript.genetic_code(), Standard);
+        assert_eq!(transcript.translate(&genomic_seq), super::translate(&transcript.coding_sequence(&genomic_seq)));
+        let mitochondrial = super::Transcript{seq_region_name: "MT".to_owned(), ..transcript};
+        assert_eq!(mitochondrial.genetic_code(), VertebrateMitochondrial);
+    }
+    #[test]
+    fn test_has_start_codon(){
+        use super::GeneticCode::*;
+        let atg = super::translate("ATGCCCTAA");
+        assert!(atg.starts_with_atg && atg.has_start_codon);
+        let no_start = super::translate("cgGCCATGTAA");
+        assert!(!no_start.starts_with_atg && !no_start.has_start_codon);
+        assert_eq!(no_start.protein_sequence, "AM*");
+        let nonstop = super::translate("CCCAAA");
+        assert_eq!(nonstop.translation_type, super::TranslationType::NONSTOP);
+        assert!(!nonstop.has_start_codon);
+        // MT-ND1 starts at ATA, an alternative start only in the mitochondrial code.
+        let mitochondrial = super::translate_with("ATAACCTAA", VertebrateMitochondrial);
+        assert!(!mitochondrial.starts_with_atg && mitochondrial.has_start_codon);
+        assert!(!super::translate_with("ATAACCTAA", Standard).has_start_codon);
+        assert!(!super::translate("").has_start_codon);
+    }
+    #[test]
+    fn test_translate_spliced(){
+        let seq = GenomicSequence {
+            query: "test".to_owned(),
+            id: "test".to_owned(),
+            desc: None,
+            seq: format!("ATGTAAgtag{}agCCC", "C".repeat(60)),
+        };
+        let (spliced, junctions) = seq.spliced_with_junctions();
+        assert_eq!(junctions, [6, 66]);
+        let translation = super::translate_spliced(&spliced, &junctions, super::GeneticCode::Standard);
+        assert_eq!(translation.protein_sequence, "M*");
+        assert_eq!(translation.stop_index, Some(6));
+        assert_eq!(translation.last_ejc_index, Some(65));
+        assert_eq!(translation.translation_type, super::TranslationType::NMD);
+        // The case based heuristic agrees here, but counts the intron towards the 50 bases.
+        assert_eq!(super::translate(&seq.seq).last_ejc_index, Some(69));
+        let single_exon = super::translate_spliced("ATGTAACCC", &[], super::GeneticCode::Standard);
+        assert_eq!(single_exon.translation_type, super::TranslationType::NORMAL);
+        let (transcript, genome) = ttr();
+        let spliced = transcript.translate_spliced(&genome);
+        assert_eq!(spliced.protein_sequence, transcript.translate(&genome).protein_sequence);
+        assert!(spliced.last_ejc_index.is_some());
+        assert_eq!(spliced.translation_type, super::TranslationType::NORMAL);
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_display(){
+        let translation = super::translate("ATGGCCTAA");
+        assert_eq!(translation.to_string(), "MA* (NORMAL)");
+        let (transcript, genomic_seq) = ttr();
+        let v30m = super::make_consequences(&genomic_seq, &transcript, 31592974, 31592974, "A").unwrap();
+        assert_eq!(v30m.to_string(), "coding p.Val50Met");
+        assert_eq!(super::Consequences::Splicing(super::SpliceRegion::Donor).to_string(), "splice donor");
+        assert_eq!(super::Consequences::LostStart.to_string(), "start lost p.Met1?");
+        assert_eq!(super::Consequences::Intron.to_string(), "intron");
+    }
+    #[test]
+    fn test_translate_many(){
+        let seqs: Vec<String> = (0..100).map(|i| format!("ATG{}TAA", "GCC".repeat(i))).collect();
+        let translations = super::translate_many(&seqs);
+        assert_eq!(translations.len(), seqs.len());
+        for (seq, translation) in seqs.iter().zip(&translations) {
+            assert_eq!(translation, &super::translate(seq));
+        }
+        assert_eq!(translations[2].protein_sequence, "MAA*");
+    }
+    #[test]
+    fn test_protein_change_kinds(){
+        use super::{translate, ProteinChange, ProteinChangeKind};
+        let between = |unedited: &str, edited: &str| ProteinChange::between(&translate(unedited), &translate(edited), edited.len() as i64 - unedited.len() as i64);
+        let kind = |unedited: &str, edited: &str| between(unedited, edited).kind;
+        assert_eq!(kind("ATGAAACCCTGA", "ATGAAACCCTGA"), ProteinChangeKind::Silent);
+        assert_eq!(kind("ATGAAACCCTGA", "ATGAAGCCCTGA"), ProteinChangeKind::Silent);
+        assert_eq!(kind("ATGAAACCCTGA", "ATGTAACCCTGA"), ProteinChangeKind::Nonsense);
+        assert_eq!(kind("ATGAAACCCTGA", "ATGCCCTGA"), ProteinChangeKind::InFrameIndel);
+        assert_eq!(kind("ATGAAACCCTGA", "ATGAAACCCTGGCCTGA"), ProteinChangeKind::StopLost);
+        let change = between("ATGAAACCCTGA", "ATGAGACCCTGA");
+        assert_eq!((change.pos, change.from, change.to, change.kind), (2, 'K', 'R', ProteinChangeKind::Missense));
+        // Changes to the last codon before the stop share only the stop, but stay in frame.
+        assert_eq!(kind("ATGGCCTCCTGA", "ATGGCCACCTGA"), ProteinChangeKind::Missense);
+        assert_eq!(kind("ATGGCCTCCTGA", "ATGGCCTGA"), ProteinChangeKind::InFrameIndel);
+        assert_eq!(kind("ATGGCCTCCTGA", "ATGGCCTGATGA"), ProteinChangeKind::Nonsense);
+    }
+    #[test]
+    fn test_hgvs_protein(){
+        use super::{hgvs_protein, translate};
+        let hgvs = |unedited: &str, edited: &str| hgvs_protein(&translate(unedited), &translate(edited), edited.len() as i64 - unedited.len() as i64);
+        assert_eq!(hgvs("ATGAAACCCTGA", "ATGAAGCCCTGA"), "p.(=)");
+        assert_eq!(hgvs("ATGAAACCCTGA", "ATGAGACCCTGA"), "p.Lys2Arg");
+        assert_eq!(hgvs("ATGAAACCCTGA", "ATGTAACCCTGA"), "p.Lys2Ter");
+        assert_eq!(hgvs("ATGAAACCCTGA", "ATGCCCTGA"), "p.Lys2del");
+        assert_eq!(hgvs("ATGAAAAAACCCTGA", "ATGAAACCCTGA"), "p.Lys3del");
+        assert_eq!(hgvs("ATGAAACCCTGA", "ATGAAATGGCCCTGA"), "p.Lys2_Pro3insTrp");
+        assert_eq!(hgvs("ATGAAACCCGGGTGA", "ATGTGGTGGGGGTGA"), "p.Lys2_Pro3delinsTrpTrp");
+        assert_eq!(hgvs("ATGAAACCCTGA", "ATGAAACCCTGGCCCTGA"), "p.Ter4TrpextTer3");
+        assert_eq!(hgvs("ATGAAACCCGGGTGA", "ATGAACCCGGGTGA"), "p.Lys2AsnfsTer?");
+        assert_eq!(hgvs("ATGAAACCCGGGTTTTGA", "ATGAACTGGTGA"), "p.Lys2_Phe5delinsAsnTrp");
+        assert_eq!(hgvs("ATGAAACTGGTGAAATGA", "ATGAACTGGTGAAATGA"), "p.Lys2AsnfsTer3");
+        // A change to the last codon before the stop is in frame, though only the stop realigns.
+        assert_eq!(hgvs("ATGGCCTCCTGA", "ATGGCCACCTGA"), "p.Ser3Thr");
+        assert_eq!(hgvs("ATGGCCTCCTGA", "ATGGCCTGA"), "p.Ser3del");
+        assert_eq!(hgvs("ATGGCCTCCTGA", "ATGGCCTGATGA"), "p.Ser3Ter");
+    }
+    #[test]
+    fn test_complement(){
+        assert_eq!(super::complement("ACgtN"), "TGcaN");
+        assert_eq!(super::reverse_complement("ACgtN"), "NacGT");
+        assert!(super::reverse_complement_iter("ACgtN").eq(super::reverse_complement("ACgtN").chars()));
+    }
+    #[test]
+    fn test_three_letter(){
+        let translation = super::translate("ATGGTGTGA");
+        assert_eq!(translation.protein_sequence_three_letter(), "MetValTer");
+    }
+    #[test]
+    fn test_coding_sequence(){
+        let (transcript, genomic_seq) = ttr();
+        let cds = transcript.coding_sequence(&genomic_seq);
+        assert_eq!(cds.len(), 3 * (transcript.translation.unwrap().length as usize + 1));
+        assert!(cds.starts_with("ATG"));
+        assert!(cds.ends_with("TGA"));
+        const TTR: &str = "MASHRLLLLCLAGLVFVSEAGPTGTGESKCPLMVKVLDAVRGSPAINVAVHVFRKAADDTWEPFASGKTSESGELHGLTTEEEFVEGIYKVEIDTKSYWKALGISPFHEHAEVVFTANDSGPRRYTIAALLSPYSYSTTAVVTNPKE*";
+        assert_eq!(super::translate(&cds).protein_sequence, TTR);
+    }
+    #[test]
+    fn test_start_codon(){
+        let (transcript, genomic_seq) = ttr();
+        // The start codon is 31591903-31591905.
+        let lost = super::make_consequences(&genomic_seq, &transcript, 31591905, 31591905, "A").unwrap();
+        assert_eq!(lost, super::Consequences::LostStart);
+        assert_eq!(lost.hgvs_protein().unwrap(), "p.Met1?");
+        let retained = super::make_consequences(&genomic_seq, &transcript, 31591905, 31591905, "G").unwrap();
+        assert!(matches!(retained, super::Consequences::StartRetained{..}));
+        assert_eq!(retained.protein_change().unwrap().kind, super::ProteinChangeKind::Silent);
+        let inserted = super::make_consequences(&genomic_seq, &transcript, 31591906, 31591905, "TGG").unwrap();
+        assert!(matches!(inserted, super::Consequences::StartRetained{..}));
+        assert_eq!(inserted.hgvs_protein().unwrap(), "p.Met1_Ala2insTrp");
+        let utr = super::make_consequences(&genomic_seq, &transcript, 31591900, 31591900, "A").unwrap();
+        assert!(matches!(utr, super::Consequences::Coding{..}));
+    }
+    #[test]
+    fn test_cds_offset_in_genomic(){
+        let (transcript, _) = ttr();
+        assert_eq!(transcript.cds_offset_in_genomic(), Some(31591903 - 31591877));
+        let minus: super::Transcript = serde_json::from_str(
+            r#"{"id":"ENST1","start":101,"end":200,"strand":-1,"species":"homo_sapiens","Translation":{"id":"ENSP1","start":121,"end":180,"length":19}}"#,
+        ).unwrap();
+        assert_eq!(minus.cds_offset_in_genomic(), Some(20));
+        let noncoding = super::Transcript{translation: None, ..minus};
+        assert_eq!(noncoding.cds_offset_in_genomic(), None);
+    }
+    #[test]
+    fn test_non_coding_exon(){
+        // The fixtures have no lncRNA, so strip TTR-201 of its translation to stand in for one.
+        let (coding, genomic_seq) = ttr();
+        let lncrna = super::Transcript{translation: None, biotype: crate::Biotype::lncRNA, ..coding};
+        let consequences = super::make_consequences(&genomic_seq, &lncrna, 31592974, 31592974, "A").unwrap();
+        let super::Consequences::NonCodingExon{edited_exonic_sequence} = &consequences else {panic!("{consequences:?}")};
+        let (unedited, _) = genomic_seq.spliced_with_junctions();
+        assert_eq!(edited_exonic_sequence.len(), unedited.len());
+        assert_eq!(edited_exonic_sequence.chars().zip(unedited.chars()).filter(|(a, b)| a != b).count(), 1);
+        assert_eq!(consequences.protein_change(), None);
+        assert_eq!(consequences.to_string(), "non-coding exon");
+        let deletion = super::make_consequences(&genomic_seq, &lncrna, 31592974, 31592974, "-").unwrap();
+        assert!(matches!(deletion, super::Consequences::NonCodingExon{edited_exonic_sequence} if edited_exonic_sequence.len() == unedited.len() - 1));
+        // Intronic variants are still reported as such.
+        assert_eq!(super::make_consequences(&genomic_seq, &lncrna, 31592000, 31592000, "A"), Ok(super::Consequences::Intron));
+    }
+    #[test]
+    fn test_exons_in_transcription_order(){
+        let (transcript, _) = ttr();
+        let ids = |t: &super::Transcript| t.exons_in_transcription_order().iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&transcript), transcript.exons.iter().map(|e| e.id.clone()).collect::<Vec<_>>());
+        let minus: super::Transcript = serde_json::from_str(
+            r#"{"id":"ENST1","start":101,"end":400,"strand":-1,"species":"homo_sapiens","Exon":[{"id":"ENSE3","start":101,"end":150,"strand":-1},{"id":"ENSE2","start":201,"end":250,"strand":-1},{"id":"ENSE1","start":351,"end":400,"strand":-1}]}"#,
+        ).unwrap();
+        assert_eq!(ids(&minus), ["ENSE1", "ENSE2", "ENSE3"]);
+        let mut shuffled = minus.clone();
+        shuffled.exons.swap(0, 1);
+        assert_eq!(ids(&shuffled), ["ENSE1", "ENSE2", "ENSE3"]);
+    }
+    #[test]
+    fn test_is_coding(){
+        let (transcript, _) = ttr();
+        assert!(transcript.is_coding());
+        assert!(transcript.has_valid_translation());
+        let cds_not_defined = super::Transcript{biotype: crate::Biotype::protein_coding_CDS_not_defined, ..transcript.clone()};
+        assert!(!cds_not_defined.is_coding());
+        let untranslated = super::Transcript{translation: None, ..transcript.clone()};
+        assert!(!untranslated.is_coding());
+        assert!(!untranslated.has_valid_translation());
+        // The first intron is 31591972-31592895.
+        let mut intronic = transcript.clone();
+        intronic.translation.as_mut().unwrap().start = 31592000;
+        assert!(intronic.is_coding());
+        assert!(!intronic.has_valid_translation());
+        let mut reversed = transcript;
+        reversed.translation.as_mut().unwrap().end = 31591900;
+        assert!(!reversed.has_valid_translation());
+    }
+    #[test]
+    fn test_exon_phases(){
+        let (transcript, _) = ttr();
+        assert_eq!(transcript.exons.iter().map(|e| e.length()).collect::<Vec<_>>(), [95, 131, 136, 254]);
+        assert_eq!(transcript.exon_phases(), [(-1, 0), (0, 2), (2, 0), (0, -1)]);
+    }
+    #[test]
+    fn test_utr_sequences(){
+        let (transcript, genomic_seq) = ttr();
+        let five_prime = transcript.five_prime_utr_sequence(&genomic_seq);
+        let three_prime = transcript.three_prime_utr_sequence(&genomic_seq);
+        assert_eq!((five_prime.len(), three_prime.len()), (26, 146));
+        let spliced: String = genomic_seq.seq.chars().filter(|c| c.is_uppercase()).collect();
+        assert_eq!(spliced, five_prime + &transcript.coding_sequence(&genomic_seq) + &three_prime);
+        let no_utrs = super::Transcript{utrs: Vec::new(), ..transcript};
+        assert_eq!(no_utrs.five_prime_utr_sequence(&genomic_seq), "");
+    }
+    #[test]
+    fn test_kozak_strength(){
+        let (transcript, genomic_seq) = ttr();
+        // TTR starts in a strong context: gcAggATGG.
+        assert_eq!(transcript.kozak_strength(&genomic_seq), Some(super::KozakStrength::Strong));
+        let weak: super::Transcript = serde_json::from_str(
+            r#"{"id":"ENST1","start":1,"end":12,"strand":1,"species":"homo_sapiens","Translation":{"id":"ENSP1","start":5,"end":10,"length":1}}"#
+        ).unwrap();
+        let weak_seq = GenomicSequence{seq: "CTTCATGATGAA".to_owned(), ..genomic_seq.clone()};
+        assert_eq!(weak.kozak_strength(&weak_seq), Some(super::KozakStrength::Weak));
+        let adequate_seq = GenomicSequence{seq: "CATCATGATGAA".to_owned(), ..genomic_seq.clone()};
+        assert_eq!(weak.kozak_strength(&adequate_seq), Some(super::KozakStrength::Adequate));
+        let short_utr = super::Transcript{start: 3, ..weak};
+        assert_eq!(short_utr.kozak_strength(&GenomicSequence{seq: "TCATGATGAA".to_owned(), ..genomic_seq}), None);
+        assert_eq!(super::KozakStrength::classify('g', 'g'), super::KozakStrength::Strong);
+        assert_eq!(super::KozakStrength::classify('C', 'G'), super::KozakStrength::Adequate);
+    }
+    #[test]
+    fn test_genomic_to_protein_pos(){
+        let (transcript, _) = ttr();
+        // V30M is numbered on the mature protein, after the 20 residue signal peptide.
+        assert_eq!(transcript.genomic_to_protein_pos(31592974), Some(50));
+        assert_eq!(transcript.genomic_to_protein_pos(31591903), Some(1));
+        assert_eq!(transcript.genomic_to_protein_pos(31598675), Some(148));
+        assert_eq!(transcript.genomic_to_protein_pos(31591880), None);
+        assert_eq!(transcript.genomic_to_protein_pos(31592000), None);
+    }
+    #[test]
+    fn test_genomic_to_cdna_pos(){
+        let (transcript, _) = ttr();
+        // VEP reports cdna_start 174 for V30M on ENST00000237014.
+        assert_eq!(transcript.genomic_to_cdna_pos(31592974), Some(174));
+        assert_eq!(transcript.genomic_to_cdna_pos(transcript.start), Some(1));
+        let length: u32 = transcript.exons.iter().map(|e| e.length()).sum();
+        assert_eq!(transcript.genomic_to_cdna_pos(transcript.end), Some(length));
+        assert_eq!(transcript.genomic_to_cdna_pos(31592000), None);
+        assert_eq!(transcript.genomic_to_cdna_pos(transcript.end + 1), None);
+        let minus: super::Transcript = serde_json::from_str(
+            r#"{"id":"ENST1","start":101,"end":400,"strand":-1,"species":"homo_sapiens","Exon":[{"id":"ENSE3","start":101,"end":150,"strand":-1},{"id":"ENSE2","start":201,"end":250,"strand":-1},{"id":"ENSE1","start":351,"end":400,"strand":-1}]}"#,
+        ).unwrap();
+        assert_eq!(minus.genomic_to_cdna_pos(400), Some(1));
+        assert_eq!(minus.genomic_to_cdna_pos(250), Some(51));
+        assert_eq!(minus.genomic_to_cdna_pos(101), Some(150));
+        assert_eq!(minus.genomic_to_cdna_pos(300), None);
+    }
+}
@@ -0,0 +1,107 @@
+//! Known variants looked up by identifier, eg dbSNP rsIDs, with their alleles, population and
+//! clinical context.
+use serde::{Deserialize, Serialize};
+
+use crate::vep::Frequency;
+
+/// A known variant looked up by its identifier, eg `"rs28933979"`.
+/// * Complements VEP for callers that already have rsIDs and want population or clinical context.
+/// ```no_run
+/// # tokio::runtime::Builder::new_current_thread()
+/// #       .enable_all()
+/// #       .build()
+/// #       .unwrap()
+/// #       .block_on(async {
+/// use rs_embl::{Getter, variation::Variation};
+///
+/// let v = Getter::<Variation>::new();
+/// let variation = v.client().get("rs28933979".to_owned()).await.unwrap();
+/// println!("{:?} {:?}", variation.minor_allele, variation.clinical_significance);
+/// # });
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct Variation {
+    pub name: String,
+    /// eg "SNP" or "deletion".
+    pub var_class: Option<String>,
+    pub source: Option<String>,
+    /// The second most frequent allele in the 1000 Genomes global population.
+    pub minor_allele: Option<String>,
+    /// The frequency of [Variation::minor_allele].
+    #[serde(rename = "MAF")]
+    pub minor_allele_freq: Option<Frequency>,
+    /// eg "pathogenic" or "benign".
+    #[serde(default)]
+    pub clinical_significance: Vec<String>,
+    /// The locations of the variant on the assembly.
+    #[serde(default)]
+    pub mappings: Vec<VariationMapping>,
+    /// Other identifiers of the variant, eg HGMD or ClinVar accessions.
+    #[serde(default)]
+    pub synonyms: Vec<String>,
+    pub most_severe_consequence: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct VariationMapping {
+    /// eg "18:31592974-31592974".
+    pub location: String,
+    pub seq_region_name: String,
+    pub start: u32,
+    pub end: u32,
+    pub strand: crate::Strand,
+    /// eg "G/A".
+    pub allele_string: String,
+    pub assembly_name: Option<String>,
+    pub ancestral_allele: Option<String>,
+}
+
+impl crate::EnsemblPostEndpoint for Variation {
+    fn extension() -> &'static str {
+        "/variation/homo_sapiens"
+    }
+    fn payload_template() -> &'static str {
+        r#"{"ids" : {ids}}"#
+    }
+    fn input(&self) -> &str {
+        &self.name
+    }
+    fn max_post_size() -> usize {
+        200
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::{Getter, Transport, TransportFuture};
+
+    const RS28933979_JSON: &str = r#"{"rs28933979":{"source":"Variants (including SNPs and indels) imported from dbSNP","mappings":[{"location":"18:31592974-31592974","assembly_name":"GRCh38","end":31592974,"seq_region_name":"18","strand":1,"coord_system":"chromosome","allele_string":"G/A","start":31592974,"ancestral_allele":"G"}],"name":"rs28933979","MAF":0.0002,"ambiguity":"R","var_class":"SNP","synonyms":["CM870001","RCV000013534"],"evidence":["Frequency","Cited"],"clinical_significance":["pathogenic","likely pathogenic"],"most_severe_consequence":"missense_variant","minor_allele":"A"}}"#;
+
+    struct FixtureTransport;
+    impl Transport for FixtureTransport {
+        fn post<'a>(
+            &'a self,
+            _url: &'a str,
+            _headers: &'a [(&'a str, &'a str)],
+            _body: String,
+        ) -> TransportFuture<'a> {
+            Box::pin(async { Ok((200, RS28933979_JSON.to_owned())) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_variation() {
+        let getter = Getter::<Variation>::builder()
+            .transport(FixtureTransport)
+            .build();
+        let variation = getter.client().get("rs28933979".to_owned()).await.unwrap();
+        assert_eq!(variation.minor_allele.as_deref(), Some("A"));
+        assert_eq!(variation.minor_allele_freq, Some(Frequency(0.0002)));
+        assert_eq!(variation.clinical_significance[0], "pathogenic");
+        assert_eq!(variation.mappings[0].start, 31592974);
+        assert!(variation.synonyms.contains(&"CM870001".to_owned()));
+        let json = serde_json::to_string(&variation).unwrap();
+        assert_eq!(serde_json::from_str::<Variation>(&json).unwrap(), variation);
+    }
+}
@@ -1,15 +1,25 @@
 //! Structures for the Variant Effect Predictor (VEP) endpoint of the Ensembl API.
 
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
-use serde::{Deserialize, Serialize};
+use regex::Regex;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use thiserror::Error;
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::GetterBuilder;
+
+/// A VEP response that tolerates results this crate cannot model.
+/// * Variants are tried in order, so a result is only [VEPResult::Unparseable] if it is neither a
+///   [VEPAnalysis] nor an [EnsemblError](crate::EnsemblError).
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum VEPResult {
     Success(VEPAnalysis),
     EnsemblError(crate::api::EnsemblError),
+    Unparseable(VEPUnparseable),
     Error,
 }
 impl VEPResult {
@@ -17,27 +27,533 @@ impl VEPResult {
         match self {
             VEPResult::Success(analysis) => &analysis.input,
             VEPResult::EnsemblError(error) => &error.input,
+            VEPResult::Unparseable(unparseable) => &unparseable.input,
             VEPResult::Error => "ERROR",
         }
     }
 }
 
+/// A VEP result that failed to parse as a [VEPAnalysis], kept as its raw top level fields.
+/// * Each value holds the JSON text of that field.
+/// * The parse failure is logged when the fallback is taken, with the `tracing` feature enabled.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(
+    from = "BTreeMap<String, serde_json::Value>",
+    into = "BTreeMap<String, serde_json::Value>"
+)]
+pub struct VEPUnparseable {
+    pub input: String,
+    pub fields: BTreeMap<String, String>,
+}
+impl VEPUnparseable {
+    /// Reparse the raw fields as a [VEPAnalysis], returning the error that caused the fallback.
+    pub fn try_into_analysis(&self) -> Result<VEPAnalysis, serde_json::Error> {
+        serde_json::from_value(serde_json::to_value(self)?)
+    }
+}
+impl From<BTreeMap<String, serde_json::Value>> for VEPUnparseable {
+    fn from(value: BTreeMap<String, serde_json::Value>) -> Self {
+        let input = match value.get("input") {
+            Some(serde_json::Value::String(input)) => input.clone(),
+            _ => String::new(),
+        };
+        let unparseable = Self {
+            input,
+            fields: stringify_fields(value),
+        };
+        #[cfg(feature = "tracing")]
+        if let Err(err) = unparseable.try_into_analysis() {
+            crate::logging::log_warn!("Unparseable VEP result for {}: {err}", unparseable.input);
+        }
+        unparseable
+    }
+}
+impl From<VEPUnparseable> for BTreeMap<String, serde_json::Value> {
+    fn from(value: VEPUnparseable) -> Self {
+        parse_fields(value.fields)
+    }
+}
+
+/// Store json values as strings so unparseable results stay [Eq] and [Hash].
+fn stringify_fields(value: BTreeMap<String, serde_json::Value>) -> BTreeMap<String, String> {
+    value
+        .into_iter()
+        .map(|(key, value)| (key, value.to_string()))
+        .collect()
+}
+
+fn parse_fields(fields: BTreeMap<String, String>) -> BTreeMap<String, serde_json::Value> {
+    fields
+        .into_iter()
+        .map(|(key, value)| {
+            let value = serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value));
+            (key, value)
+        })
+        .collect()
+}
+
 /// A successful VEP analysis result.
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
 pub struct VEPAnalysis {
     pub input: String,
     #[serde(default)]
     pub id: String,
-    pub strand: i8,
+    pub strand: crate::Strand,
     pub assembly_name: String,
     pub seq_region_name: String,
     pub most_severe_consequence: String,
+    /// The class Ensembl assigns the variant, requested with [VepOptions::variant_class].
+    /// * Compare with [Allele::variant_type] through [VariantClass::variant_type].
+    pub variant_class: Option<VariantClass>,
     pub start: u32,
     pub end: u32,
     #[serde(rename = "allele_string")]
     pub allele: Allele,
     #[serde(default)]
-    pub transcript_consequences: Vec<TranscriptConsequence>,
+    pub transcript_consequences: Vec<TranscriptConsequenceResponse>,
+    #[serde(default)]
+    pub colocated_variants: Vec<ColocatedVariant>,
+    /// Consequences on regulatory features, requested with [VepOptions::regulatory].
+    /// * Resolve the features themselves with [crate::regulatory::regulatory_feature].
+    #[serde(default)]
+    pub regulatory_feature_consequences: Vec<RegulatoryConsequence>,
+    /// Consequences on transcription factor binding motifs, requested with [VepOptions::regulatory].
+    #[serde(default)]
+    pub motif_feature_consequences: Vec<MotifConsequence>,
+}
+impl VEPAnalysis {
+    /// The transcript consequences that parsed as [TranscriptConsequence]s, skipping the rest.
+    pub fn parseable_consequences(&self) -> impl Iterator<Item = &TranscriptConsequence> {
+        self.transcript_consequences
+            .iter()
+            .filter_map(TranscriptConsequenceResponse::as_parseable)
+    }
+
+    /// The consequence on the canonical transcript, if it parsed.
+    pub fn canonical_consequence(&self) -> Option<&TranscriptConsequence> {
+        self.parseable_consequences()
+            .find(|tc| tc.canonical == crate::Canonical::CANONICAL)
+    }
+
+    /// The parseable transcript consequences grouped by gene, for per gene reports where genes overlap.
+    /// * Keyed by [TranscriptConsequence::gene_id], or the gene symbol if VEP gave no gene ID.
+    /// * Each gene's consequences keep the order VEP returned them in.
+    pub fn consequences_by_gene(&self) -> BTreeMap<String, Vec<&TranscriptConsequence>> {
+        let mut genes: BTreeMap<String, Vec<&TranscriptConsequence>> = BTreeMap::new();
+        for tc in self.parseable_consequences() {
+            let gene = if tc.gene_id.is_empty() {
+                &tc.gene_symbol
+            } else {
+                &tc.gene_id
+            };
+            genes.entry(gene.clone()).or_default().push(tc);
+        }
+        genes
+    }
+
+    /// The most severe consequence across the parseable [VEPAnalysis::transcript_consequences].
+    /// * Unlike [VEPAnalysis::most_severe_consequence], follows any filtering of the transcript
+    ///   consequences, eg to MANE transcripts, and ignores regulatory consequences.
+    /// * [crate::Consequence::Unknown] when there are no transcript consequences.
+    pub fn derive_most_severe(&self) -> crate::Consequence {
+        let terms: Vec<_> = self
+            .parseable_consequences()
+            .flat_map(TranscriptConsequence::consequences)
+            .collect();
+        crate::most_severe_consequence(&terms)
+    }
+
+    /// The gnomAD allele frequencies of this variant's alternate alleles, keyed by population
+    /// (eg `gnomade`, `gnomade_nfe`, `gnomadg_afr`).
+    /// * Requires the analysis to be requested with [VepOptions::af_gnomad].
+    /// * Where several colocated variants report a population the highest frequency is kept.
+    /// * Returns an empty map when no frequencies were reported.
+    pub fn gnomad_frequencies(&self) -> BTreeMap<String, f64> {
+        let mut output = self.frequencies().populations;
+        output.retain(|population, _| population.starts_with("gnomad"));
+        output
+    }
+
+    /// The highest frequency of this variant's alternate alleles in `population`, across 1000
+    /// Genomes and the gnomAD exomes and genomes.
+    /// * Requires the analysis to be requested with [VepOptions::af] or [VepOptions::af_gnomad].
+    /// * [None] when no frequency was reported for the population.
+    /// ```
+    /// # use rs_embl::vep::{Population, VEPAnalysis};
+    /// # fn exclude(vep: &VEPAnalysis) -> bool {
+    /// vep.frequency(Population::Nfe).is_some_and(|af| af > 0.01)
+    /// # }
+    /// ```
+    pub fn frequency(&self, population: Population) -> Option<f64> {
+        self.frequencies()
+            .populations
+            .into_iter()
+            .filter(|(key, _)| Population::from(key.as_str()) == population)
+            .map(|(_, frequency)| frequency)
+            .reduce(f64::max)
+    }
+
+    /// The allele frequencies of this variant's alternate alleles, requested with
+    /// [VepOptions::af] and [VepOptions::af_gnomad], or together with [VepRequest::frequencies].
+    /// * Where several colocated variants report a population the highest frequency is kept.
+    /// * VEP keys a deleted allele's frequencies as `-`, matched to the empty [Allele::variants] entry.
+    pub fn frequencies(&self) -> AlleleFrequencies {
+        let mut populations = BTreeMap::new();
+        for colocated in self.colocated_variants.iter() {
+            let colocated_populations = colocated
+                .frequencies
+                .iter()
+                .filter(|(allele, _)| {
+                    let allele = if allele.as_str() == "-" { "" } else { allele };
+                    self.allele.variants.iter().any(|variant| variant == allele)
+                })
+                .flat_map(|(_, populations)| populations.iter());
+            for (population, frequency) in colocated_populations {
+                let entry = populations.entry(population.clone()).or_insert(frequency.0);
+                *entry = entry.max(frequency.0);
+            }
+        }
+        AlleleFrequencies {
+            af: populations.get("af").copied(),
+            gnomade: populations.get("gnomade").copied(),
+            gnomadg: populations.get("gnomadg").copied(),
+            populations,
+        }
+    }
+
+    /// The header row for [VEPAnalysis::to_tsv_rows].
+    pub fn tsv_header() -> String {
+        TSV_COLUMNS.join("\t")
+    }
+
+    /// One tab separated row per transcript consequence, with the columns of [VEPAnalysis::tsv_header].
+    /// * Consequence terms are joined with commas and canonical is written as `1` or `0`.
+    /// * Variants without transcript consequences get a single row with the transcript columns empty.
+    /// * Tabs and newlines within values are replaced by spaces.
+    pub fn to_tsv_rows(&self) -> Vec<String> {
+        if self.transcript_consequences.is_empty() {
+            let mut row = vec![String::new(); TSV_COLUMNS.len()];
+            row[0] = self.input.clone();
+            return vec![tsv_row(row)];
+        }
+        self.transcript_consequences
+            .iter()
+            .map(|tc| match tc {
+                TranscriptConsequenceResponse::Parseable(tc) => {
+                    let protein = tc.protein_consequences.as_ref();
+                    tsv_row(vec![
+                        self.input.clone(),
+                        tc.gene_symbol.clone(),
+                        tc.transcript_id.clone(),
+                        tc.consequence_terms.join(","),
+                        tc.impact.clone().unwrap_or_default(),
+                        protein.map(|p| p.hgvsc.clone()).unwrap_or_default(),
+                        protein.map(|p| p.hgvsp.clone()).unwrap_or_default(),
+                        i32::from(tc.canonical).to_string(),
+                        tc.biotype.clone().unwrap_or_default(),
+                    ])
+                }
+                TranscriptConsequenceResponse::Unparseable(tc) => {
+                    let mut row = vec![self.input.clone()];
+                    row.extend(TSV_COLUMNS[1..].iter().map(|column| tc.tsv_field(column)));
+                    tsv_row(row)
+                }
+            })
+            .collect()
+    }
+}
+
+const TSV_COLUMNS: [&str; 9] = [
+    "input",
+    "gene_symbol",
+    "transcript_id",
+    "consequence_terms",
+    "impact",
+    "hgvsc",
+    "hgvsp",
+    "canonical",
+    "biotype",
+];
+
+fn tsv_row(fields: Vec<String>) -> String {
+    fields
+        .iter()
+        .map(|field| field.replace(['\t', '\n', '\r'], " "))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+/// A known variant overlapping the analysed variant.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct ColocatedVariant {
+    #[serde(default)]
+    pub id: String,
+    pub start: Option<u32>,
+    pub end: Option<u32>,
+    pub allele_string: Option<String>,
+    /// Allele frequencies keyed by allele, then population.
+    #[serde(default)]
+    pub frequencies: BTreeMap<String, BTreeMap<String, Frequency>>,
+}
+
+/// The frequencies of a variant's alternate alleles, from [VEPAnalysis::frequencies].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlleleFrequencies {
+    /// The 1000 Genomes global frequency, requested with [VepOptions::af].
+    pub af: Option<f64>,
+    /// The gnomAD exomes global frequency, requested with [VepOptions::af_gnomad].
+    pub gnomade: Option<f64>,
+    /// The gnomAD genomes global frequency, requested with [VepOptions::af_gnomad].
+    pub gnomadg: Option<f64>,
+    /// Every reported population, including the global ones, eg `gnomade_nfe` or `afr`.
+    pub populations: BTreeMap<String, f64>,
+}
+
+/// A population VEP reports allele frequencies for, independent of the dataset.
+/// * Parsed from the frequency keys with their dataset prefix dropped, so `gnomade_nfe` and
+///   `gnomadg_nfe` are both [Population::Nfe], and `af`, `gnomade` and `gnomadg` are [Population::Global].
+/// ```
+/// use rs_embl::vep::Population;
+/// assert_eq!(Population::from("gnomadg_afr"), Population::Afr);
+/// assert_eq!(Population::from("af"), Population::Global);
+/// assert_eq!(Population::from("gnomade_remaining"), Population::Other("remaining".to_owned()));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub enum Population {
+    Global,
+    /// African / African American.
+    Afr,
+    /// Admixed American.
+    Amr,
+    /// Amish.
+    Ami,
+    /// Ashkenazi Jewish.
+    Asj,
+    /// East Asian.
+    Eas,
+    /// European, reported by 1000 Genomes.
+    Eur,
+    /// Finnish.
+    Fin,
+    /// Middle Eastern.
+    Mid,
+    /// Non-Finnish European.
+    Nfe,
+    /// Other, reported by gnomAD as "oth".
+    Oth,
+    /// South Asian.
+    Sas,
+    Other(String),
+}
+impl From<&str> for Population {
+    fn from(value: &str) -> Self {
+        let population = match value.split_once('_') {
+            Some((dataset, population)) if dataset.starts_with("gnomad") => population,
+            None if value == "af" || value.starts_with("gnomad") => return Population::Global,
+            _ => value,
+        };
+        match population.to_ascii_lowercase().as_str() {
+            "afr" => Population::Afr,
+            "amr" => Population::Amr,
+            "ami" => Population::Ami,
+            "asj" => Population::Asj,
+            "eas" => Population::Eas,
+            "eur" => Population::Eur,
+            "fin" => Population::Fin,
+            "mid" => Population::Mid,
+            "nfe" => Population::Nfe,
+            "oth" => Population::Oth,
+            "sas" => Population::Sas,
+            other => Population::Other(other.to_owned()),
+        }
+    }
+}
+
+/// An allele frequency. Compared and hashed bitwise so results containing it stay [Eq] and [Hash].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialOrd)]
+#[serde(transparent)]
+pub struct Frequency(pub f64);
+impl PartialEq for Frequency {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+impl Eq for Frequency {}
+impl std::hash::Hash for Frequency {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+/// The consequence of a variant on an overlapping regulatory feature.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct RegulatoryConsequence {
+    pub regulatory_feature_id: String,
+    pub impact: Option<String>,
+    #[serde(default)]
+    pub biotype: crate::RegulatoryBiotype,
+    #[serde(default)]
+    pub consequence_terms: Vec<String>,
+    pub variant_allele: Option<String>,
+}
+
+/// The consequence of a variant on a transcription factor binding motif.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct MotifConsequence {
+    pub motif_feature_id: String,
+    pub motif_name: Option<String>,
+    /// The position of the variant within the motif.
+    pub motif_pos: Option<u32>,
+    /// Whether the variant falls at a high information position of the motif.
+    #[serde(default)]
+    pub high_inf_pos: HighInfPos,
+    #[serde(default)]
+    pub transcription_factors: Vec<String>,
+    pub impact: Option<String>,
+    #[serde(default)]
+    pub consequence_terms: Vec<String>,
+    pub variant_allele: Option<String>,
+}
+impl MotifConsequence {
+    /// Whether the variant falls at a high information position, or [None] if VEP didn't say.
+    pub fn high_inf_pos_bool(&self) -> Option<bool> {
+        match self.high_inf_pos {
+            HighInfPos::Y => Some(true),
+            HighInfPos::N => Some(false),
+            HighInfPos::null => None,
+        }
+    }
+}
+
+/// VEP's `"Y"`/`"N"` flag for a high information motif position, `null` when it isn't reported.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(from = "Option<String>", into = "Option<String>")]
+#[allow(non_camel_case_types)]
+pub enum HighInfPos {
+    Y,
+    N,
+    #[default]
+    null,
+}
+impl From<Option<String>> for HighInfPos {
+    fn from(value: Option<String>) -> Self {
+        match value.as_deref() {
+            Some("Y") => HighInfPos::Y,
+            Some("N") => HighInfPos::N,
+            _ => HighInfPos::null,
+        }
+    }
+}
+impl From<HighInfPos> for Option<String> {
+    fn from(value: HighInfPos) -> Self {
+        match value {
+            HighInfPos::Y => Some("Y".to_owned()),
+            HighInfPos::N => Some("N".to_owned()),
+            HighInfPos::null => None,
+        }
+    }
+}
+/// `N` and `null` are both false, use [MotifConsequence::high_inf_pos_bool] to tell them apart.
+impl From<HighInfPos> for bool {
+    fn from(value: HighInfPos) -> Self {
+        value == HighInfPos::Y
+    }
+}
+
+/// A transcript consequence that tolerates shapes this crate cannot model, so a single odd
+/// consequence doesn't fail its whole [VEPAnalysis].
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum TranscriptConsequenceResponse {
+    Parseable(TranscriptConsequence),
+    Unparseable(UnparseableConsequence),
+}
+impl TranscriptConsequenceResponse {
+    /// The transcript the consequence is on, if the response named one.
+    pub fn transcript_id(&self) -> Option<&str> {
+        match self {
+            Self::Parseable(tc) => Some(&tc.transcript_id),
+            Self::Unparseable(tc) => tc.transcript_id.as_deref(),
+        }
+    }
+
+    pub fn as_parseable(&self) -> Option<&TranscriptConsequence> {
+        match self {
+            Self::Parseable(tc) => Some(tc),
+            Self::Unparseable(_) => None,
+        }
+    }
+}
+
+/// The raw fields of a transcript consequence that couldn't be parsed as a [TranscriptConsequence].
+/// * Field values are stored as json strings.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(
+    from = "BTreeMap<String, serde_json::Value>",
+    into = "BTreeMap<String, serde_json::Value>"
+)]
+pub struct UnparseableConsequence {
+    pub transcript_id: Option<String>,
+    pub fields: BTreeMap<String, String>,
+}
+impl From<BTreeMap<String, serde_json::Value>> for UnparseableConsequence {
+    fn from(value: BTreeMap<String, serde_json::Value>) -> Self {
+        let transcript_id = match value.get("transcript_id") {
+            Some(serde_json::Value::String(id)) => Some(id.clone()),
+            _ => None,
+        };
+        Self {
+            transcript_id,
+            fields: stringify_fields(value),
+        }
+    }
+}
+impl UnparseableConsequence {
+    /// A field rendered for a TSV cell: strings unquoted, arrays comma joined, missing fields empty.
+    fn tsv_field(&self, key: &str) -> String {
+        let Some(value) = self.fields.get(key) else {
+            return String::new();
+        };
+        match serde_json::from_str(value) {
+            Ok(serde_json::Value::String(value)) => value,
+            Ok(serde_json::Value::Array(values)) => values
+                .iter()
+                .map(|value| match value {
+                    serde_json::Value::String(value) => value.clone(),
+                    value => value.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(","),
+            _ => value.clone(),
+        }
+    }
+}
+impl From<UnparseableConsequence> for BTreeMap<String, serde_json::Value> {
+    fn from(value: UnparseableConsequence) -> Self {
+        parse_fields(value.fields)
+    }
+}
+
+/// One line summaries: `input gene:symbol most_severe`, eg
+/// `18:g.31592974G>A gene:TTR missense_variant`.
+/// * The gene is that of the canonical transcript, else of the first transcript naming one, and is
+///   left out for intergenic variants.
+impl std::fmt::Display for VEPAnalysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.input)?;
+        let gene = self
+            .canonical_consequence()
+            .filter(|tc| !tc.gene_symbol.is_empty())
+            .or_else(|| {
+                self.parseable_consequences()
+                    .find(|tc| !tc.gene_symbol.is_empty())
+            });
+        if let Some(tc) = gene {
+            write!(f, " gene:{}", tc.gene_symbol)?;
+        }
+        write!(f, " {}", self.most_severe_consequence)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
@@ -60,6 +576,161 @@ pub struct TranscriptConsequence {
     pub cdna_end: Option<u32>,
     pub exon: Option<String>,
     pub intron: Option<String>,
+    /// Transcript support level, requested with [VepOptions::tsl].
+    /// * Parsed from a number or a string such as `"1 (assigned to previous version 3)"`, and
+    ///   [None] for `"NA"`.
+    #[serde(default, deserialize_with = "tsl_from_number_or_string")]
+    pub tsl: Option<u8>,
+    /// APPRIS isoform annotation, requested with [VepOptions::appris].
+    pub appris: Option<String>,
+    /// CCDS identifier, requested with [VepOptions::ccds].
+    pub ccds: Option<String>,
+    /// RefSeq identifier of the matching MANE Select transcript, requested with [VepOptions::mane].
+    pub mane_select: Option<String>,
+    /// RefSeq identifier of the matching MANE Plus Clinical transcript, requested with [VepOptions::mane].
+    pub mane_plus_clinical: Option<String>,
+    /// The protein domains overlapping the variant, requested with [VepOptions::domains].
+    #[serde(default)]
+    pub domains: Vec<ProteinDomain>,
+    /// Flags marking an incomplete transcript model, see [TranscriptConsequence::is_cds_incomplete].
+    #[serde(default)]
+    pub flags: Vec<TranscriptFlag>,
+    /// The reference allele given in the HGVS notation, requested with [VepOptions::ambiguous_hgvs].
+    pub given_ref: Option<String>,
+    /// The reference allele VEP used, from the genome, requested with [VepOptions::ambiguous_hgvs].
+    pub used_ref: Option<String>,
+}
+impl TranscriptConsequence {
+    /// Whether this is the MANE Select transcript for its gene.
+    /// Always false unless the analysis was requested with [VepOptions::mane].
+    /// ```
+    /// use rs_embl::vep::TranscriptConsequence;
+    /// let tc: TranscriptConsequence = serde_json::from_str(
+    ///     r#"{"transcript_id": "ENST00000237014", "mane_select": "NM_000371.4"}"#,
+    /// ).unwrap();
+    /// assert!(tc.is_mane_select());
+    /// ```
+    pub fn is_mane_select(&self) -> bool {
+        self.mane_select.is_some()
+    }
+
+    /// Whether the annotated CDS lacks its start or end, so an offline translation of the
+    /// transcript can't be trusted.
+    pub fn is_cds_incomplete(&self) -> bool {
+        self.flags.iter().any(|flag| {
+            matches!(
+                flag,
+                TranscriptFlag::cds_start_NF | TranscriptFlag::cds_end_NF
+            )
+        })
+    }
+
+    /// Whether VEP replaced the reference allele given in the HGVS notation with the genome's.
+    /// Always false unless the analysis was requested with [VepOptions::ambiguous_hgvs].
+    pub fn is_reference_corrected(&self) -> bool {
+        matches!((&self.given_ref, &self.used_ref), (Some(given), Some(used)) if given != used)
+    }
+
+    /// The [consequence_terms](TranscriptConsequence::consequence_terms) as [Consequence](crate::Consequence)s.
+    pub fn consequences(&self) -> Vec<crate::Consequence> {
+        self.consequence_terms
+            .iter()
+            .map(|term| crate::Consequence::from(term.as_str()))
+            .collect()
+    }
+
+    /// The affected exon and the transcript's exon count, parsed from [TranscriptConsequence::exon].
+    /// * For a variant spanning several exons, eg `"2-3/4"`, the first affected exon.
+    /// * [None] if the variant isn't in an exon or the value is malformed.
+    /// ```
+    /// use rs_embl::vep::TranscriptConsequence;
+    /// let tc: TranscriptConsequence =
+    ///     serde_json::from_str(r#"{"transcript_id": "ENST00000237014", "exon": "4/4"}"#).unwrap();
+    /// assert_eq!(tc.exon_rank(), Some((4, 4)));
+    /// assert_eq!(tc.intron_rank(), None);
+    /// ```
+    pub fn exon_rank(&self) -> Option<(u32, u32)> {
+        parse_rank(self.exon.as_deref()?)
+    }
+
+    /// The affected intron and the transcript's intron count, parsed from [TranscriptConsequence::intron].
+    /// * Follows the same rules as [TranscriptConsequence::exon_rank].
+    pub fn intron_rank(&self) -> Option<(u32, u32)> {
+        parse_rank(self.intron.as_deref()?)
+    }
+}
+
+/// Parse a VEP `"rank/total"` or `"first-last/total"` string into the first rank and the total.
+fn parse_rank(value: &str) -> Option<(u32, u32)> {
+    let (ranks, total) = value.split_once('/')?;
+    let rank = ranks.split('-').next()?.trim().parse().ok()?;
+    let total = total.trim().parse().ok()?;
+    (rank >= 1 && rank <= total).then_some((rank, total))
+}
+
+/// `transcript_id gene_symbol consequence_terms`, then the HGVS notations if VEP gave any, eg
+/// `ENST00000237014 TTR missense_variant ENST00000237014.8:c.148G>A ENSP00000237014.4:p.Val50Met`.
+impl std::fmt::Display for TranscriptConsequence {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.transcript_id,
+            self.gene_symbol,
+            self.consequence_terms.join(",")
+        )?;
+        if let Some(protein) = &self.protein_consequences {
+            write!(f, " {} {}", protein.hgvsc, protein.hgvsp)?;
+        }
+        Ok(())
+    }
+}
+
+/// A flag VEP reports for an incomplete transcript model.
+/// * Flags this crate doesn't know deserialize as [TranscriptFlag::Unknown].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum TranscriptFlag {
+    /// The CDS start couldn't be confirmed.
+    cds_start_NF,
+    /// The CDS end couldn't be confirmed.
+    cds_end_NF,
+    /// The mRNA start couldn't be confirmed.
+    mRNA_start_NF,
+    /// The mRNA end couldn't be confirmed.
+    mRNA_end_NF,
+    #[serde(other)]
+    Unknown,
+}
+
+fn tsl_from_number_or_string<'de, D>(deserializer: D) -> Result<Option<u8>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        match Option::<serde_json::Value>::deserialize(deserializer)? {
+            Some(serde_json::Value::Number(tsl)) => {
+                tsl.as_u64().and_then(|tsl| u8::try_from(tsl).ok())
+            }
+            Some(serde_json::Value::String(tsl)) => {
+                let digits: String = tsl
+                    .trim()
+                    .chars()
+                    .take_while(char::is_ascii_digit)
+                    .collect();
+                digits.parse().ok()
+            }
+            _ => None,
+        },
+    )
+}
+
+/// A protein domain annotation, eg `{"db": "Pfam", "name": "PF00576"}`.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct ProteinDomain {
+    /// The source database, eg "Pfam" or "PROSITE_profiles".
+    pub db: String,
+    pub name: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
@@ -73,13 +744,373 @@ pub struct ProteinConsequence {
     pub codons: String,
     pub amino_acids: String,
 }
+impl ProteinConsequence {
+    /// Parse [ProteinConsequence::hgvsp] into its components, see [HgvsProtein].
+    /// # Errors
+    ///
+    /// Returns a [HgvsProteinError] if the notation isn't one of the forms [HgvsProtein] handles.
+    pub fn hgvs_protein(&self) -> Result<HgvsProtein, HgvsProteinError> {
+        self.hgvsp.parse()
+    }
+
+    /// The reference codon(s) from [ProteinConsequence::codons], uppercased, eg `GTG` for `gTg/gAg`.
+    /// * `-` for an insertion between codons, [None] if `codons` isn't of the form `ref/alt`.
+    pub fn reference_codon(&self) -> Option<String> {
+        self.codons
+            .split_once('/')
+            .map(|(reference, _)| reference.to_ascii_uppercase())
+    }
+
+    /// The alternate codon(s) from [ProteinConsequence::codons], uppercased, eg `GAG` for `gTg/gAg`.
+    /// * `-` for a deletion of whole codons, [None] if `codons` isn't of the form `ref/alt`.
+    pub fn alternate_codon(&self) -> Option<String> {
+        self.codons
+            .split_once('/')
+            .map(|(_, alternate)| alternate.to_ascii_uppercase())
+    }
+
+    /// The 0-based positions within the codon(s) where the reference and alternate bases differ,
+    /// eg `[1]` for `gTg/gAg`.
+    /// * [None] for insertions and deletions, where the codons aren't the same length.
+    pub fn changed_base_positions(&self) -> Option<Vec<usize>> {
+        let (reference, alternate) = (self.reference_codon()?, self.alternate_codon()?);
+        if reference.len() != alternate.len() {
+            return None;
+        }
+        Some(
+            reference
+                .bytes()
+                .zip(alternate.bytes())
+                .enumerate()
+                .filter(|(_, (r, a))| r != a)
+                .map(|(i, _)| i)
+                .collect(),
+        )
+    }
+}
+
+/// The components of an HGVS protein notation like `ENSP00000237014.4:p.Val50Met`.
+/// * Handles substitutions, synonymous changes, nonsense changes and frameshifts, with either
+///   three letter amino acids or `*` for stops.
+/// * `=` may be URI escaped as `%3D`, as VEP reports it.
+/// ```
+/// use rs_embl::vep::{HgvsProtein, ProteinEdit};
+/// let hgvs: HgvsProtein = "ENSP00000237014.4:p.Val50Met".parse().unwrap();
+/// assert_eq!(hgvs.position, Some(50));
+/// assert_eq!(hgvs.edit, ProteinEdit::Substitution("Met".to_owned()));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct HgvsProtein {
+    /// The protein the notation is relative to, if given.
+    pub protein_id: Option<String>,
+    /// The three letter reference amino acid, or [None] for `p.(=)`.
+    pub reference: Option<String>,
+    /// The 1-based position of the reference amino acid, or [None] for `p.(=)`.
+    pub position: Option<u32>,
+    pub edit: ProteinEdit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub enum ProteinEdit {
+    /// The protein, or the reference amino acid, is unchanged.
+    Synonymous,
+    /// The reference amino acid is replaced by this three letter amino acid.
+    Substitution(String),
+    /// The reference amino acid is replaced by a stop codon.
+    Nonsense,
+    /// A frameshift starting at the reference amino acid.
+    Frameshift {
+        /// The first amino acid of the new reading frame, if given.
+        alternate: Option<String>,
+        /// The position of the new stop codon within the new frame, if known.
+        stop: Option<u32>,
+    },
+}
+
+impl FromStr for HgvsProtein {
+    type Err = HgvsProteinError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unrecognized = || HgvsProteinError::Unrecognized(s.to_owned());
+        let unescaped = s.replace("%3D", "=");
+        let (protein_id, description) = match unescaped.split_once(':') {
+            Some((id, description)) => (Some(id.to_owned()), description),
+            None => (None, unescaped.as_str()),
+        };
+        let description = description.strip_prefix("p.").ok_or_else(unrecognized)?;
+        let description = description
+            .strip_prefix('(')
+            .and_then(|d| d.strip_suffix(')'))
+            .unwrap_or(description);
+        if description == "=" {
+            return Ok(Self {
+                protein_id,
+                reference: None,
+                position: None,
+                edit: ProteinEdit::Synonymous,
+            });
+        }
+        let captures = Regex::new(r"^([A-Z][a-z]{2})([0-9]+)(.+)$")
+            .unwrap()
+            .captures(description)
+            .ok_or_else(unrecognized)?;
+        let edit = match &captures[3] {
+            "=" => ProteinEdit::Synonymous,
+            "Ter" | "*" => ProteinEdit::Nonsense,
+            alternate if Regex::new(r"^[A-Z][a-z]{2}$").unwrap().is_match(alternate) => {
+                ProteinEdit::Substitution(alternate.to_owned())
+            }
+            frameshift => {
+                let captures = Regex::new(r"^([A-Z][a-z]{2})?fs(?:(?:Ter|\*)([0-9]+|\?))?$")
+                    .unwrap()
+                    .captures(frameshift)
+                    .ok_or_else(unrecognized)?;
+                ProteinEdit::Frameshift {
+                    alternate: captures.get(1).map(|m| m.as_str().to_owned()),
+                    stop: captures.get(2).and_then(|m| m.as_str().parse().ok()),
+                }
+            }
+        };
+        Ok(Self {
+            protein_id,
+            reference: Some(captures[1].to_owned()),
+            position: Some(captures[2].parse().map_err(|_| unrecognized())?),
+            edit,
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum HgvsProteinError {
+    #[error("`{0}` is not a recognized HGVS protein notation")]
+    Unrecognized(String),
+}
+
+/// Flags controlling which annotations VEP adds to its response.
+/// * The default matches the flags the VEP endpoints have always requested.
+/// * Attach to a [Getter](crate::Getter) with [GetterBuilder::vep_options].
+/// ```
+/// use rs_embl::{EnsemblPostEndpoint, vep::{VepOptions, VEPAnalysis}};
+/// let default: serde_json::Value = serde_json::from_str(
+///     &VepOptions::default().payload_template("hgvs_notations").replace("{ids}", "[]"),
+/// ).unwrap();
+/// let fixed: serde_json::Value =
+///     serde_json::from_str(&VEPAnalysis::payload_template().replace("{ids}", "[]")).unwrap();
+/// assert_eq!(default, fixed);
+///
+/// let options = VepOptions { tsl: true, mane: true, ..Default::default() };
+/// assert!(options.payload_template("ids").contains(r#""mane": 1"#));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VepOptions {
+    pub hgvs: bool,
+    pub numbers: bool,
+    pub canonical: bool,
+    pub nmd: bool,
+    pub tsl: bool,
+    pub domains: bool,
+    pub regulatory: bool,
+    pub mane: bool,
+    pub appris: bool,
+    pub ccds: bool,
+    pub af: bool,
+    pub af_gnomad: bool,
+    pub variant_class: bool,
+    /// Let VEP resolve ambiguous HGVS notations, eg with a mismatched reference, instead of failing them.
+    /// * See [TranscriptConsequence::given_ref] and [TranscriptConsequence::used_ref].
+    pub ambiguous_hgvs: bool,
+}
+impl Default for VepOptions {
+    fn default() -> Self {
+        Self {
+            hgvs: true,
+            numbers: true,
+            canonical: true,
+            nmd: true,
+            tsl: false,
+            domains: false,
+            regulatory: false,
+            mane: false,
+            appris: false,
+            ccds: false,
+            af: false,
+            af_gnomad: false,
+            variant_class: true,
+            ambiguous_hgvs: false,
+        }
+    }
+}
+impl VepOptions {
+    /// Compose the payload template, listing the identifiers under `ids_key`.
+    pub fn payload_template(&self, ids_key: &str) -> String {
+        let flags = [
+            ("hgvs", self.hgvs),
+            ("numbers", self.numbers),
+            ("canonical", self.canonical),
+            ("NMD", self.nmd),
+            ("tsl", self.tsl),
+            ("domains", self.domains),
+            ("regulatory", self.regulatory),
+            ("mane", self.mane),
+            ("appris", self.appris),
+            ("ccds", self.ccds),
+            ("af", self.af),
+            ("af_gnomad", self.af_gnomad),
+            ("variant_class", self.variant_class),
+            ("ambiguous_hgvs", self.ambiguous_hgvs),
+        ];
+        let mut payload = String::from("{");
+        for (flag, _) in flags.iter().filter(|(_, enabled)| *enabled) {
+            payload.push_str(&format!(r#""{flag}": 1, "#));
+        }
+        payload.push_str(&format!(r#""{ids_key}" : {{ids}}}}"#));
+        payload
+    }
+}
+
+/// Compose a VEP request from field sets, and the [GetterBuilder] parsing its responses.
+/// * Starts from no annotations, unlike [VepOptions::default], so each field set is explicit.
+/// * Each field set is parsed into typed fields: [VepRequest::frequencies] into
+///   [VEPAnalysis::frequencies] and [VepRequest::domains] into [TranscriptConsequence::domains].
+/// ```
+/// use rs_embl::vep::{VepRequest, VEPAnalysis};
+/// let filtering = VepRequest::new().frequencies();
+/// assert_eq!(
+///     filtering.payload_template::<VEPAnalysis>(),
+///     r#"{"af": 1, "af_gnomad": 1, "hgvs_notations" : {ids}}"#
+/// );
+/// let reporting = VepRequest::new().protein().domains();
+/// assert!(reporting.options().hgvs && reporting.options().domains);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VepRequest {
+    options: VepOptions,
+}
+impl Default for VepRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl VepRequest {
+    /// A request with no optional annotations.
+    pub fn new() -> Self {
+        Self {
+            options: VepOptions {
+                hgvs: false,
+                numbers: false,
+                canonical: false,
+                nmd: false,
+                variant_class: false,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// 1000 Genomes and gnomAD allele frequencies, parsed by [VEPAnalysis::frequencies].
+    pub fn frequencies(mut self) -> Self {
+        self.options.af = true;
+        self.options.af_gnomad = true;
+        self
+    }
+
+    /// HGVS notations, exon and intron numbers, canonical and NMD flags, as needed by
+    /// [TranscriptConsequence::protein_consequences] and [TranscriptConsequence::canonical].
+    pub fn protein(mut self) -> Self {
+        self.options.hgvs = true;
+        self.options.numbers = true;
+        self.options.canonical = true;
+        self.options.nmd = true;
+        self
+    }
+
+    /// Protein domains, parsed into [TranscriptConsequence::domains].
+    pub fn domains(mut self) -> Self {
+        self.options.domains = true;
+        self
+    }
+
+    /// TSL, APPRIS, CCDS and MANE annotations of each transcript.
+    pub fn transcript_annotations(mut self) -> Self {
+        self.options.tsl = true;
+        self.options.appris = true;
+        self.options.ccds = true;
+        self.options.mane = true;
+        self
+    }
+
+    /// Regulatory and motif feature consequences.
+    pub fn regulatory(mut self) -> Self {
+        self.options.regulatory = true;
+        self
+    }
+
+    /// The variant class, parsed into [VEPAnalysis::variant_class].
+    pub fn variant_class(mut self) -> Self {
+        self.options.variant_class = true;
+        self
+    }
+
+    /// Resolve ambiguous HGVS notations rather than failing them, see [VepOptions::ambiguous_hgvs].
+    pub fn ambiguous_hgvs(mut self) -> Self {
+        self.options.ambiguous_hgvs = true;
+        self
+    }
+
+    pub fn options(&self) -> VepOptions {
+        self.options
+    }
+
+    /// The payload template for the VEP endpoint `T`, as used by [crate::EnsemblPostEndpoint].
+    pub fn payload_template<T: VEPEndpoint>(&self) -> String {
+        self.options.payload_template(T::ids_key())
+    }
+
+    /// A [GetterBuilder] requesting this field set and parsing the responses as `T`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn getter<T: 'static + VEPEndpoint + Send + DeserializeOwned>(&self) -> GetterBuilder<T> {
+        crate::Getter::<T>::builder().vep_options(self.options)
+    }
+}
+
+/// The VEP endpoints, which share [VepOptions] but list their identifiers under different keys.
+pub trait VEPEndpoint: crate::EnsemblPostEndpoint {
+    /// The payload key holding the identifiers, eg `"hgvs_notations"`.
+    fn ids_key() -> &'static str;
+}
+impl VEPEndpoint for VEPAnalysis {
+    fn ids_key() -> &'static str {
+        "hgvs_notations"
+    }
+}
+impl VEPEndpoint for VEPResult {
+    fn ids_key() -> &'static str {
+        "hgvs_notations"
+    }
+}
+impl VEPEndpoint for VEPById {
+    fn ids_key() -> &'static str {
+        "ids"
+    }
+}
+impl VEPEndpoint for VEPByRegion {
+    fn ids_key() -> &'static str {
+        "variants"
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: 'static + VEPEndpoint + Send + DeserializeOwned> GetterBuilder<T> {
+    /// Request the VEP annotations selected by `options`.
+    pub fn vep_options(self, options: VepOptions) -> Self {
+        self.payload_template(options.payload_template(T::ids_key()))
+    }
+}
 
 impl crate::EnsemblPostEndpoint for VEPAnalysis {
     fn extension() -> &'static str {
         "/vep/human/hgvs"
     }
     fn payload_template() -> &'static str {
-        r#"{"hgvs": 1, "numbers": 1, "canonical" : 1, "NMD" : 1, "hgvs_notations" : {ids}}"#
+        r#"{"hgvs": 1, "numbers": 1, "canonical" : 1, "NMD" : 1, "variant_class" : 1, "hgvs_notations" : {ids}}"#
     }
     fn input(&self) -> &str {
         &self.input
@@ -94,7 +1125,7 @@ impl crate::EnsemblPostEndpoint for VEPResult {
         "/vep/human/hgvs"
     }
     fn payload_template() -> &'static str {
-        r#"{"hgvs": 1, "numbers": 1, "canonical" : 1, "NMD" : 1, "hgvs_notations" : {ids}}"#
+        r#"{"hgvs": 1, "numbers": 1, "canonical" : 1, "NMD" : 1, "variant_class" : 1, "hgvs_notations" : {ids}}"#
     }
     fn input(&self) -> &str {
         self.input()
@@ -104,11 +1135,319 @@ impl crate::EnsemblPostEndpoint for VEPResult {
     }
 }
 
+/// A VEP analysis requested by variant identifier (eg a dbSNP rsID) rather than HGVS notation.
+/// * Dereferences to the underlying [VEPAnalysis], whose `input` is the identifier requested.
+/// ```no_run
+/// # tokio::runtime::Builder::new_current_thread()
+/// #       .enable_all()
+/// #       .build()
+/// #       .unwrap()
+/// #       .block_on(async {
+/// use rs_embl::{Getter, vep::VEPById};
+///
+/// let v = Getter::<VEPById>::new();
+/// let vep = v.client().get("rs699".to_owned()).await.unwrap();
+/// println!("{}", vep.most_severe_consequence);
+/// # });
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct VEPById(pub VEPAnalysis);
+impl std::ops::Deref for VEPById {
+    type Target = VEPAnalysis;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl From<VEPById> for VEPAnalysis {
+    fn from(value: VEPById) -> Self {
+        value.0
+    }
+}
+
+impl crate::EnsemblPostEndpoint for VEPById {
+    fn extension() -> &'static str {
+        "/vep/human/id"
+    }
+    fn payload_template() -> &'static str {
+        r#"{"hgvs": 1, "numbers": 1, "canonical" : 1, "NMD" : 1, "variant_class" : 1, "ids" : {ids}}"#
+    }
+    fn input(&self) -> &str {
+        &self.0.input
+    }
+    fn max_post_size() -> usize {
+        200
+    }
+}
+
+/// A VEP analysis requested by genomic region, in the VCF-like form `"chr start id ref alt"`.
+/// * Dereferences to the underlying [VEPAnalysis], whose `input` is the region requested.
+/// * Regions are checked with [VEPRegion] before they are queued. Build them with [VEPRegion::to_string].
+/// ```no_run
+/// # tokio::runtime::Builder::new_current_thread()
+/// #       .enable_all()
+/// #       .build()
+/// #       .unwrap()
+/// #       .block_on(async {
+/// use rs_embl::{Getter, vep::{VEPByRegion, VEPRegion}};
+///
+/// let v = Getter::<VEPByRegion>::new();
+/// let region: VEPRegion = "18 31592974 . G A".parse().unwrap();
+/// let vep = v.client().get(region.to_string()).await.unwrap();
+/// println!("{}", vep.most_severe_consequence);
+/// # });
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct VEPByRegion(pub VEPAnalysis);
+impl std::ops::Deref for VEPByRegion {
+    type Target = VEPAnalysis;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl From<VEPByRegion> for VEPAnalysis {
+    fn from(value: VEPByRegion) -> Self {
+        value.0
+    }
+}
+
+impl crate::EnsemblPostEndpoint for VEPByRegion {
+    fn extension() -> &'static str {
+        "/vep/human/region"
+    }
+    fn payload_template() -> &'static str {
+        r#"{"hgvs": 1, "numbers": 1, "canonical" : 1, "NMD" : 1, "variant_class" : 1, "variants" : {ids}}"#
+    }
+    fn input(&self) -> &str {
+        &self.0.input
+    }
+    fn max_post_size() -> usize {
+        200
+    }
+    fn check_input(input: &str) -> Result<(), String> {
+        VEPRegion::from_str(input)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// A variant in the VCF-like region form accepted by the VEP region endpoint.
+/// ```
+/// use rs_embl::vep::VEPRegion;
+/// let region: VEPRegion = "18 31592974 . G A".parse().unwrap();
+/// assert_eq!(region.position, 31592974);
+/// assert_eq!(region.to_string(), "18 31592974 . G A . . .");
+/// assert!("18 31592974 G A".parse::<VEPRegion>().is_err());
+/// assert!("18 0 . G A".parse::<VEPRegion>().is_err());
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct VEPRegion {
+    pub chromosome: String,
+    pub position: u32,
+    pub id: Option<String>,
+    pub reference: String,
+    pub alternate: Vec<String>,
+}
+impl FromStr for VEPRegion {
+    type Err = RegionParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let [chromosome, position, id, reference, alternate, ..] = fields[..] else {
+            return Err(RegionParseError::MissingFields(fields.len()));
+        };
+        let position = match position.parse::<u32>() {
+            Ok(position) if position > 0 => position,
+            _ => return Err(RegionParseError::InvalidPosition(position.to_owned())),
+        };
+        if !is_vcf_allele(reference) {
+            return Err(RegionParseError::InvalidAllele(reference.to_owned()));
+        }
+        let alternate: Vec<String> = alternate.split(',').map(|a| a.to_owned()).collect();
+        if let Some(allele) = alternate.iter().find(|a| !is_vcf_allele(a)) {
+            return Err(RegionParseError::InvalidAllele(allele.to_owned()));
+        }
+        Ok(Self {
+            chromosome: chromosome.to_owned(),
+            position,
+            id: (id != ".").then(|| id.to_owned()),
+            reference: reference.to_owned(),
+            alternate,
+        })
+    }
+}
+impl std::fmt::Display for VEPRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} . . .",
+            self.chromosome,
+            self.position,
+            self.id.as_deref().unwrap_or("."),
+            self.reference,
+            self.alternate.join(",")
+        )
+    }
+}
+fn is_vcf_allele(allele: &str) -> bool {
+    allele == "-"
+        || (!allele.is_empty()
+            && allele
+                .chars()
+                .all(|c| matches!(c.to_ascii_uppercase(), 'A' | 'C' | 'G' | 'T' | 'N')))
+}
+
+#[derive(Error, Debug)]
+pub enum RegionParseError {
+    #[error("Regions need at least 5 fields (chr start id ref alt), found {0}")]
+    MissingFields(usize),
+    #[error("`{0}` is not a valid 1-based position")]
+    InvalidPosition(String),
+    #[error("`{0}` is not a valid allele")]
+    InvalidAllele(String),
+}
+
+/// Sanity check an HGVS string before sending it to VEP, so obvious mistakes fail locally.
+/// * Checks the reference sequence, the coordinate type and the characters used.
+/// * Genomic (`g.`) and mitochondrial (`m.`) variants must also have a valid position and one
+///   of the common edits: a substitution, `del`, `dup`, `ins`, `delins`, `inv` or `=`.
+/// * This is not a full HGVS parser, so some strings passing it may still be rejected by Ensembl.
+/// ```
+/// use rs_embl::vep::{validate_hgvs, HgvsError};
+/// assert!(validate_hgvs("18:g.31592974G>A").is_ok());
+/// assert!(validate_hgvs("ENST00000237014.8:c.148G>A").is_ok());
+/// assert!(matches!(validate_hgvs("g.31592974G>A"), Err(HgvsError::MissingReference)));
+/// assert!(matches!(validate_hgvs("18:g.0G>A"), Err(HgvsError::InvalidPosition(_))));
+/// ```
+pub fn validate_hgvs(s: &str) -> Result<(), HgvsError> {
+    if s.is_empty() {
+        return Err(HgvsError::Empty);
+    }
+    if let Some(c) = s
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || "_.:>+-*=()[];,?".contains(*c)))
+    {
+        return Err(HgvsError::IllegalCharacter(c));
+    }
+    let Some((reference, description)) = s.split_once(':') else {
+        return Err(HgvsError::MissingReference);
+    };
+    if reference.is_empty() {
+        return Err(HgvsError::MissingReference);
+    }
+    let coordinate_type = match description.split_once('.') {
+        Some((coordinate_type @ ("g" | "m" | "c" | "n" | "r" | "p"), _)) => coordinate_type,
+        _ => return Err(HgvsError::InvalidCoordinateType(description.to_owned())),
+    };
+    if !matches!(coordinate_type, "g" | "m") {
+        return Ok(());
+    }
+    let variant = &description[2..];
+    let Some(position) = Regex::new(r"^[1-9][0-9]*(_[1-9][0-9]*)?")
+        .unwrap()
+        .find(variant)
+    else {
+        return Err(HgvsError::InvalidPosition(variant.to_owned()));
+    };
+    let edit = &variant[position.end()..];
+    let edit_regex = Regex::new(
+        r"^(?i:[ACGTN]>[ACGTN]|del[ACGTN]*|dup[ACGTN]*|ins[ACGTN]+|delins[ACGTN]+|inv|=)$",
+    )
+    .unwrap();
+    if !edit_regex.is_match(edit) {
+        return Err(HgvsError::InvalidEdit(edit.to_owned()));
+    }
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum HgvsError {
+    #[error("HGVS strings can't be empty")]
+    Empty,
+    #[error("`{0}` is not allowed in HGVS strings")]
+    IllegalCharacter(char),
+    #[error("HGVS strings need a reference sequence, eg `18:g.31592974G>A`")]
+    MissingReference,
+    #[error("`{0}` does not start with a coordinate type such as `g.` or `c.`")]
+    InvalidCoordinateType(String),
+    #[error("`{0}` does not start with a valid 1-based position or range")]
+    InvalidPosition(String),
+    #[error("`{0}` is not a recognized edit")]
+    InvalidEdit(String),
+}
+
+/// Build a genomic HGVS string, eg for a [VEPAnalysis] getter, from the fields of a VCF record.
+/// * `pos` is the 1-based VCF position of the first base of `ref_`.
+/// * Bases shared by the start, then the end, of both alleles (such as the VCF anchor base) are
+///   trimmed before the variant is described.
+/// * Produces a substitution, `del`, `ins` or `delins`, or `=` if the alleles are identical.
+/// ```
+/// use rs_embl::vep::hgvs_from_vcf;
+/// assert_eq!(hgvs_from_vcf("18", 31592974, "G", "A"), "18:g.31592974G>A");
+/// assert_eq!(hgvs_from_vcf("3", 46373452, "TC", "T"), "3:g.46373453del");
+/// assert_eq!(hgvs_from_vcf("1", 1000, "A", "ATG"), "1:g.1000_1001insTG");
+/// ```
+pub fn hgvs_from_vcf(chrom: &str, pos: u32, ref_: &str, alt: &str) -> String {
+    let (ref_, alt) = (ref_.to_ascii_uppercase(), alt.to_ascii_uppercase());
+    if ref_ == alt {
+        return match ref_.len() {
+            0 | 1 => format!("{chrom}:g.{pos}="),
+            len => format!("{chrom}:g.{pos}_{}=", pos + len as u32 - 1),
+        };
+    }
+    let prefix = ref_
+        .bytes()
+        .zip(alt.bytes())
+        .take_while(|(r, a)| r == a)
+        .count();
+    let (ref_, alt) = (&ref_[prefix..], &alt[prefix..]);
+    let suffix = ref_
+        .bytes()
+        .rev()
+        .zip(alt.bytes().rev())
+        .take_while(|(r, a)| r == a)
+        .count();
+    let (ref_, alt) = (&ref_[..ref_.len() - suffix], &alt[..alt.len() - suffix]);
+    let start = pos + prefix as u32;
+    let span = match ref_.len() {
+        0 => format!("{}_{start}", start - 1),
+        1 => start.to_string(),
+        len => format!("{start}_{}", start + len as u32 - 1),
+    };
+    match (ref_.len(), alt.len()) {
+        (1, 1) => format!("{chrom}:g.{span}{ref_}>{alt}"),
+        (0, _) => format!("{chrom}:g.{span}ins{alt}"),
+        (_, 0) => format!("{chrom}:g.{span}del"),
+        _ => format!("{chrom}:g.{span}delins{alt}"),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn check_hgvs(input: &str) -> Result<(), String> {
+    validate_hgvs(input).map_err(|e| e.to_string())
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl GetterBuilder<VEPAnalysis> {
+    /// Check requests with [validate_hgvs] before they are queued.
+    pub fn validate_hgvs(self) -> Self {
+        self.check_input(check_hgvs)
+    }
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl GetterBuilder<VEPResult> {
+    /// Check requests with [validate_hgvs] before they are queued.
+    pub fn validate_hgvs(self) -> Self {
+        self.check_input(check_hgvs)
+    }
+}
+
+/// The reference and alternate alleles of a variant, parsed from a VEP `allele_string` like `"A/G/T"`.
+/// * A `-` (an absent allele in an insertion or deletion) is stored as an empty string.
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
-#[serde(try_from = "String")]
+#[serde(try_from = "String", into = "String")]
 pub struct Allele {
     pub normal: String,
-    pub variant: String,
+    pub variants: Vec<String>,
 }
 impl TryFrom<String> for Allele {
     type Error = AlleleParseError;
@@ -119,18 +1458,91 @@ impl TryFrom<String> for Allele {
 impl FromStr for Allele {
     type Err = AlleleParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let Some(mid) = s.find("/") else {
+        if !s.contains('/') {
             return Err(AlleleParseError::NoSlash);
-        };
-        let mut normal = s[..mid].to_owned();
-        let mut variant = s[mid + 1..].to_owned();
-        if normal == "-" {
-            normal = "".to_owned();
-        };
-        if variant == "-" {
-            variant = "".to_owned();
-        };
-        Ok(Self { normal, variant })
+        }
+        let mut alleles = s.split('/').map(|allele| match allele {
+            "-" => "".to_owned(),
+            allele => allele.to_owned(),
+        });
+        let normal = alleles.next().unwrap_or_default();
+        Ok(Self {
+            normal,
+            variants: alleles.collect(),
+        })
+    }
+}
+impl Allele {
+    /// Classify the variant from the lengths of the reference and first alternate allele.
+    pub fn variant_type(&self) -> VariantType {
+        let variant = self
+            .variants
+            .first()
+            .map(String::as_str)
+            .unwrap_or_default();
+        match (self.normal.len(), variant.len()) {
+            (0, _) => VariantType::Insertion,
+            (_, 0) => VariantType::Deletion,
+            (1, 1) => VariantType::SNV,
+            (normal, variant) if normal == variant => VariantType::MNV,
+            _ => VariantType::Indel,
+        }
+    }
+}
+
+/// The class of a variant, as derived offline by [Allele::variant_type].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum VariantType {
+    /// A single nucleotide substitution.
+    SNV,
+    /// Bases added with none removed.
+    Insertion,
+    /// Bases removed with none added.
+    Deletion,
+    /// Bases removed and a different number of bases added.
+    Indel,
+    /// Several adjacent nucleotides substituted.
+    MNV,
+}
+
+/// The class of a variant as reported by Ensembl in [VEPAnalysis::variant_class].
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+pub enum VariantClass {
+    SNV,
+    insertion,
+    deletion,
+    indel,
+    /// Several adjacent nucleotides substituted.
+    substitution,
+    tandem_repeat,
+    sequence_alteration,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+impl VariantClass {
+    /// The equivalent [VariantType], or [None] for classes [Allele::variant_type] doesn't derive.
+    pub fn variant_type(&self) -> Option<VariantType> {
+        Some(match self {
+            VariantClass::SNV => VariantType::SNV,
+            VariantClass::insertion => VariantType::Insertion,
+            VariantClass::deletion => VariantType::Deletion,
+            VariantClass::indel => VariantType::Indel,
+            VariantClass::substitution => VariantType::MNV,
+            _ => return None,
+        })
+    }
+}
+
+impl From<Allele> for String {
+    fn from(value: Allele) -> Self {
+        std::iter::once(&value.normal)
+            .chain(value.variants.iter())
+            .map(|allele| if allele.is_empty() { "-" } else { allele })
+            .collect::<Vec<_>>()
+            .join("/")
     }
 }
 
@@ -139,3 +1551,621 @@ pub enum AlleleParseError {
     #[error("Allele strings need to conatain a /")]
     NoSlash,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTR_V30M_VEP_JSON: &str = r#"[{"input":"18:g.31592974G>A","id":"18:g.31592974G>A","strand":1,"assembly_name":"GRCh38","seq_region_name":"18","start":31592974,"end":31592974,"allele_string":"G/A","most_severe_consequence":"missense_variant","variant_class":"SNV","colocated_variants":[{"id":"rs28933979","start":31592974,"end":31592974,"strand":1,"allele_string":"G/A","frequencies":{"A":{"gnomade":0.0001194,"gnomade_afr":0,"gnomade_nfe":0.0001055,"gnomadg":0.0001051,"gnomadg_afr":0.0000241,"af":0.0002}}},{"id":"CM870001","start":31592974,"end":31592974,"strand":1,"allele_string":"HGMD_MUTATION"}],"transcript_consequences":[{"transcript_id":"ENST00000237014","gene_id":"ENSG00000118271","gene_symbol":"TTR","biotype":"protein_coding","impact":"MODERATE","consequence_terms":["missense_variant"],"canonical":1,"strand":1,"exon":"2/4","hgvsc":"ENST00000237014.8:c.148G>A","hgvsp":"ENSP00000237014.4:p.Val50Met","cdna_start":174,"cdna_end":174,"cds_start":148,"cds_end":148,"protein_start":50,"protein_end":50,"codons":"Gtg/Atg","amino_acids":"V/M","tsl":1,"mane_select":"NM_000371.4"},{"transcript_id":"ENST00000541025","gene_id":"ENSG00000118271","gene_symbol":"TTR","biotype":"protein_coding","impact":"MODERATE","consequence_terms":["missense_variant"],"strand":1,"exon":"2/3","hgvsc":"ENST00000541025.5:c.148G>A","hgvsp":"ENSP00000438532.1:p.Val50Met","cdna_start":206,"cdna_end":206,"cds_start":148,"cds_end":148,"protein_start":50,"protein_end":50,"codons":"Gtg/Atg","amino_acids":"V/M","tsl":2}]}]"#;
+
+    fn ttr_v30m() -> VEPAnalysis {
+        serde_json::from_str::<Vec<VEPAnalysis>>(TTR_V30M_VEP_JSON)
+            .unwrap()
+            .remove(0)
+    }
+
+    #[test]
+    fn test_derive_most_severe() {
+        let mut vep: VEPAnalysis = serde_json::from_value(serde_json::json!({
+            "input": "test",
+            "strand": 1,
+            "assembly_name": "GRCh38",
+            "seq_region_name": "1",
+            "most_severe_consequence": "stop_gained",
+            "start": 100,
+            "end": 100,
+            "allele_string": "C/T",
+            "transcript_consequences": [
+                {"transcript_id": "ENST1", "consequence_terms": ["stop_gained", "splice_region_variant"]},
+                {"transcript_id": "ENST2", "mane_select": "NM_1.1", "consequence_terms": ["missense_variant"]},
+                {"transcript_id": "ENST3", "consequence_terms": ["intron_variant"]}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(vep.derive_most_severe(), crate::Consequence::stop_gained);
+        vep.transcript_consequences.retain(|tc| {
+            tc.as_parseable()
+                .is_some_and(TranscriptConsequence::is_mane_select)
+        });
+        assert_eq!(
+            vep.derive_most_severe(),
+            crate::Consequence::missense_variant
+        );
+        vep.transcript_consequences.clear();
+        assert_eq!(vep.derive_most_severe(), crate::Consequence::Unknown);
+        assert_eq!(
+            ttr_v30m().derive_most_severe(),
+            crate::Consequence::missense_variant
+        );
+    }
+
+    #[test]
+    fn test_gnomad_frequencies() {
+        let vep = ttr_v30m();
+        let frequencies = vep.gnomad_frequencies();
+        assert_eq!(frequencies.len(), 5);
+        assert_eq!(frequencies["gnomade_nfe"], 0.0001055);
+        assert!(!frequencies.contains_key("af"));
+        let mut without = vep.clone();
+        without.colocated_variants.clear();
+        assert!(without.gnomad_frequencies().is_empty());
+        let frequencies = vep.frequencies();
+        assert_eq!(frequencies.af, Some(0.0002));
+        assert_eq!(frequencies.gnomade, Some(0.0001194));
+        assert_eq!(frequencies.gnomadg, Some(0.0001051));
+        assert_eq!(frequencies.populations.len(), 6);
+        assert_eq!(without.frequencies(), AlleleFrequencies::default());
+        assert_eq!(vep.frequency(Population::Nfe), Some(0.0001055));
+        assert_eq!(vep.frequency(Population::Afr), Some(0.0000241));
+        assert_eq!(vep.frequency(Population::Global), Some(0.0002));
+        assert_eq!(vep.frequency(Population::Eas), None);
+        assert_eq!(without.frequency(Population::Global), None);
+    }
+
+    #[test]
+    fn test_deletion_frequencies() {
+        let vep: VEPAnalysis = serde_json::from_value(serde_json::json!({
+            "input": "1:g.101_102del",
+            "strand": 1,
+            "assembly_name": "GRCh38",
+            "seq_region_name": "1",
+            "start": 101,
+            "end": 102,
+            "allele_string": "AG/-",
+            "most_severe_consequence": "frameshift_variant",
+            "colocated_variants": [{
+                "id": "rs1",
+                "start": 101,
+                "end": 102,
+                "allele_string": "AG/-/AGAG",
+                "frequencies": {
+                    "-": {"af": 0.01, "gnomade": 0.02, "gnomade_nfe": 0.03},
+                    "AGAG": {"af": 0.2, "gnomade": 0.3}
+                }
+            }]
+        }))
+        .unwrap();
+        assert_eq!(vep.allele.variants, [""]);
+        let frequencies = vep.frequencies();
+        assert_eq!(frequencies.af, Some(0.01));
+        assert_eq!(frequencies.gnomade, Some(0.02));
+        assert_eq!(frequencies.populations.len(), 3);
+        assert_eq!(vep.gnomad_frequencies().len(), 2);
+        assert_eq!(vep.frequency(Population::Nfe), Some(0.03));
+    }
+
+    #[test]
+    fn test_vep_request() {
+        let request = VepRequest::new().protein().domains();
+        let payload: serde_json::Value =
+            serde_json::from_str(&request.payload_template::<VEPById>().replace("{ids}", "[]"))
+                .unwrap();
+        assert_eq!(payload["domains"], 1);
+        assert_eq!(payload["ids"], serde_json::json!([]));
+        assert!(payload.get("af_gnomad").is_none());
+        let tc: TranscriptConsequence = serde_json::from_str(
+            r#"{"transcript_id":"ENST00000237014","domains":[{"db":"Pfam","name":"PF00576"},{"db":"Gene3D","name":"2.60.40.180"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(tc.domains.len(), 2);
+        assert_eq!(tc.domains[0].db, "Pfam");
+        assert!(ttr_v30m()
+            .parseable_consequences()
+            .all(|tc| tc.domains.is_empty()));
+    }
+
+    #[test]
+    fn test_ambiguous_hgvs() {
+        assert!(!VepOptions::default()
+            .payload_template("hgvs_notations")
+            .contains("ambiguous_hgvs"));
+        let template = VepRequest::new()
+            .ambiguous_hgvs()
+            .payload_template::<VEPAnalysis>();
+        let payload: serde_json::Value =
+            serde_json::from_str(&template.replace("{ids}", "[]")).unwrap();
+        assert_eq!(payload["ambiguous_hgvs"], 1);
+        let corrected: TranscriptConsequence = serde_json::from_str(
+            r#"{"transcript_id":"ENST00000237014","given_ref":"C","used_ref":"G"}"#,
+        )
+        .unwrap();
+        assert_eq!(corrected.given_ref.as_deref(), Some("C"));
+        assert!(corrected.is_reference_corrected());
+        let confirmed: TranscriptConsequence = serde_json::from_str(
+            r#"{"transcript_id":"ENST00000237014","given_ref":"G","used_ref":"G"}"#,
+        )
+        .unwrap();
+        assert!(!confirmed.is_reference_corrected());
+        assert!(!ttr_v30m()
+            .parseable_consequences()
+            .any(TranscriptConsequence::is_reference_corrected));
+    }
+
+    #[test]
+    fn test_consequences_by_gene() {
+        let vep = ttr_v30m();
+        let genes = vep.consequences_by_gene();
+        assert_eq!(genes.keys().collect::<Vec<_>>(), ["ENSG00000118271"]);
+        assert_eq!(genes["ENSG00000118271"].len(), 2);
+        let mut overlapping = vep.clone();
+        if let TranscriptConsequenceResponse::Parseable(tc) =
+            &mut overlapping.transcript_consequences[1]
+        {
+            tc.gene_id = String::new();
+            tc.gene_symbol = "TTR-AS1".to_owned();
+        }
+        let genes = overlapping.consequences_by_gene();
+        assert_eq!(genes.len(), 2);
+        assert_eq!(genes["ENSG00000118271"][0].transcript_id, "ENST00000237014");
+        assert_eq!(genes["TTR-AS1"][0].transcript_id, "ENST00000541025");
+    }
+
+    #[test]
+    fn test_display() {
+        let vep = ttr_v30m();
+        assert_eq!(
+            vep.to_string(),
+            "18:g.31592974G>A gene:TTR missense_variant"
+        );
+        let intergenic = VEPAnalysis {
+            transcript_consequences: Vec::new(),
+            most_severe_consequence: "intergenic_variant".to_owned(),
+            ..vep.clone()
+        };
+        assert_eq!(
+            intergenic.to_string(),
+            "18:g.31592974G>A intergenic_variant"
+        );
+        assert_eq!(
+            vep.canonical_consequence().unwrap().to_string(),
+            "ENST00000237014 TTR missense_variant ENST00000237014.8:c.148G>A ENSP00000237014.4:p.Val50Met"
+        );
+    }
+
+    #[test]
+    fn test_tsl_and_flags() {
+        let parse = |json: &str| serde_json::from_str::<TranscriptConsequence>(json).unwrap();
+        assert_eq!(parse(r#"{"transcript_id":"ENST1","tsl":1}"#).tsl, Some(1));
+        assert_eq!(
+            parse(r#"{"transcript_id":"ENST1","tsl":"1 (assigned to previous version 3)"}"#).tsl,
+            Some(1)
+        );
+        assert_eq!(parse(r#"{"transcript_id":"ENST1","tsl":"5"}"#).tsl, Some(5));
+        assert_eq!(parse(r#"{"transcript_id":"ENST1","tsl":"NA"}"#).tsl, None);
+        assert_eq!(parse(r#"{"transcript_id":"ENST1"}"#).tsl, None);
+        let incomplete = parse(r#"{"transcript_id":"ENST1","flags":["cds_end_NF","made_up_NF"]}"#);
+        assert_eq!(
+            incomplete.flags,
+            [TranscriptFlag::cds_end_NF, TranscriptFlag::Unknown]
+        );
+        assert!(incomplete.is_cds_incomplete());
+        let mrna = parse(r#"{"transcript_id":"ENST1","flags":["mRNA_start_NF"]}"#);
+        assert!(!mrna.is_cds_incomplete());
+        assert!(!ttr_v30m()
+            .parseable_consequences()
+            .any(TranscriptConsequence::is_cds_incomplete));
+    }
+
+    #[test]
+    fn test_exon_and_intron_rank() {
+        let parse = |json: &str| serde_json::from_str::<TranscriptConsequence>(json).unwrap();
+        let vep = ttr_v30m();
+        let tc = vep
+            .parseable_consequences()
+            .find(|tc| tc.exon.as_deref() == Some("2/4"))
+            .unwrap();
+        assert_eq!(tc.exon_rank(), Some((2, 4)));
+        assert_eq!(tc.intron_rank(), None);
+        let intron = parse(r#"{"transcript_id":"ENST1","intron":"1/3"}"#);
+        assert_eq!(intron.intron_rank(), Some((1, 3)));
+        assert_eq!(intron.exon_rank(), None);
+        assert_eq!(
+            parse(r#"{"transcript_id":"ENST1","exon":"2-3/4"}"#).exon_rank(),
+            Some((2, 4))
+        );
+        for malformed in ["", "3", "x/4", "3/", "5/4", "0/4"] {
+            assert_eq!(parse_rank(malformed), None, "{malformed}");
+        }
+    }
+
+    #[test]
+    fn test_regulatory_feature_consequences() {
+        assert!(ttr_v30m().regulatory_feature_consequences.is_empty());
+        let json = TTR_V30M_VEP_JSON.replace(
+            r#""most_severe_consequence":"missense_variant","#,
+            r#""most_severe_consequence":"missense_variant","regulatory_feature_consequences":[{"regulatory_feature_id":"ENSR00000105783","biotype":"promoter","impact":"MODIFIER","variant_allele":"A","consequence_terms":["regulatory_region_variant"]}],"#,
+        );
+        let vep = serde_json::from_str::<Vec<VEPAnalysis>>(&json)
+            .unwrap()
+            .remove(0);
+        let regulatory = &vep.regulatory_feature_consequences[0];
+        assert_eq!(regulatory.regulatory_feature_id, "ENSR00000105783");
+        assert_eq!(regulatory.biotype, crate::RegulatoryBiotype::promoter);
+        assert_eq!(regulatory.consequence_terms, ["regulatory_region_variant"]);
+    }
+
+    #[test]
+    fn test_motif_high_inf_pos() {
+        let motifs: Vec<MotifConsequence> = serde_json::from_str(
+            r#"[
+                {"motif_feature_id":"ENSM00522771531","motif_name":"ENSPFM0542","motif_pos":7,"high_inf_pos":"Y","transcription_factors":["CTCF"]},
+                {"motif_feature_id":"ENSM00522771531","high_inf_pos":"N"},
+                {"motif_feature_id":"ENSM00522771531","high_inf_pos":null},
+                {"motif_feature_id":"ENSM00522771531"}
+            ]"#,
+        )
+        .unwrap();
+        let flags: Vec<_> = motifs.iter().map(|m| m.high_inf_pos_bool()).collect();
+        assert_eq!(flags, [Some(true), Some(false), None, None]);
+        assert!(!bool::from(motifs[2].high_inf_pos));
+        let json = serde_json::to_string(&motifs[1]).unwrap();
+        assert_eq!(
+            serde_json::from_str::<MotifConsequence>(&json).unwrap(),
+            motifs[1]
+        );
+    }
+
+    #[test]
+    fn test_unparseable_transcript_consequence() {
+        let json = TTR_V30M_VEP_JSON.replace(
+            r#""transcript_id":"ENST00000541025","#,
+            r#""transcript_id":"ENST00000541025","intron":5,"#,
+        );
+        let vep = serde_json::from_str::<Vec<VEPAnalysis>>(&json)
+            .unwrap()
+            .remove(0);
+        let ids: Vec<_> = vep
+            .transcript_consequences
+            .iter()
+            .map(|tc| tc.transcript_id())
+            .collect();
+        assert_eq!(ids, [Some("ENST00000237014"), Some("ENST00000541025")]);
+        assert!(vep.transcript_consequences[0].as_parseable().is_some());
+        assert!(vep.transcript_consequences[1].as_parseable().is_none());
+        let parseable: Vec<_> = vep
+            .parseable_consequences()
+            .map(|tc| tc.transcript_id.as_str())
+            .collect();
+        assert_eq!(parseable, ["ENST00000237014"]);
+        assert_eq!(
+            vep.canonical_consequence()
+                .map(|tc| tc.transcript_id.as_str()),
+            Some("ENST00000237014")
+        );
+        let unparseable = &vep.transcript_consequences[1];
+        let round_trip = serde_json::to_string(unparseable).unwrap();
+        assert_eq!(
+            &serde_json::from_str::<TranscriptConsequenceResponse>(&round_trip).unwrap(),
+            unparseable
+        );
+    }
+
+    #[test]
+    fn test_validate_hgvs() {
+        for valid in [
+            "18:g.31592974G>A",
+            "3:g.46373453_46373484del",
+            "10:g.72346580_72346583dup",
+            "1:g.1000_1001insAT",
+            "1:g.1000delinsGG",
+            "MT:m.8993T>G",
+            "ENST00000237014.8:c.-20+1G>A",
+            "ENSP00000237014.4:p.Val50Met",
+        ] {
+            assert!(validate_hgvs(valid).is_ok(), "{valid}");
+        }
+        assert!(matches!(validate_hgvs(""), Err(HgvsError::Empty)));
+        assert!(matches!(
+            validate_hgvs("18:g.31592974 G>A"),
+            Err(HgvsError::IllegalCharacter(' '))
+        ));
+        assert!(matches!(
+            validate_hgvs(":g.31592974G>A"),
+            Err(HgvsError::MissingReference)
+        ));
+        assert!(matches!(
+            validate_hgvs("18:x.31592974G>A"),
+            Err(HgvsError::InvalidCoordinateType(_))
+        ));
+        assert!(matches!(
+            validate_hgvs("18:g.G>A"),
+            Err(HgvsError::InvalidPosition(_))
+        ));
+        assert!(matches!(
+            validate_hgvs("18:g.31592974G>"),
+            Err(HgvsError::InvalidEdit(_))
+        ));
+    }
+
+    #[test]
+    fn test_hgvs_from_vcf() {
+        assert_eq!(hgvs_from_vcf("18", 31592974, "G", "A"), "18:g.31592974G>A");
+        assert_eq!(hgvs_from_vcf("18", 31592974, "g", "a"), "18:g.31592974G>A");
+        // Deletions drop the anchor base.
+        assert_eq!(hgvs_from_vcf("3", 46373452, "TC", "T"), "3:g.46373453del");
+        assert_eq!(
+            hgvs_from_vcf("3", 46373452, "TCCA", "T"),
+            "3:g.46373453_46373455del"
+        );
+        // Insertions are between the anchor base and the next.
+        assert_eq!(hgvs_from_vcf("1", 1000, "A", "AT"), "1:g.1000_1001insT");
+        assert_eq!(hgvs_from_vcf("1", 1000, "AC", "ATTC"), "1:g.1000_1001insTT");
+        assert_eq!(
+            hgvs_from_vcf("1", 1000, "ACG", "ATT"),
+            "1:g.1001_1002delinsTT"
+        );
+        assert_eq!(
+            hgvs_from_vcf("1", 1000, "ACG", "AT"),
+            "1:g.1001_1002delinsT"
+        );
+        assert_eq!(
+            hgvs_from_vcf("1", 1000, "AC", "GT"),
+            "1:g.1000_1001delinsGT"
+        );
+        assert_eq!(hgvs_from_vcf("1", 1000, "A", "A"), "1:g.1000=");
+        for (ref_, alt) in [("G", "A"), ("TC", "T"), ("A", "AT"), ("ACG", "ATT")] {
+            assert!(validate_hgvs(&hgvs_from_vcf("1", 1000, ref_, alt)).is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_hgvs_getter() {
+        let getter = crate::Getter::<VEPAnalysis>::builder()
+            .validate_hgvs()
+            .build();
+        let err = getter
+            .client()
+            .get("18:31592974G>A".to_owned())
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code, 0);
+        assert!(err.error.contains("coordinate type"));
+    }
+
+    #[test]
+    fn test_tsv_rows() {
+        let vep = ttr_v30m();
+        assert_eq!(
+            VEPAnalysis::tsv_header(),
+            "input\tgene_symbol\ttranscript_id\tconsequence_terms\timpact\thgvsc\thgvsp\tcanonical\tbiotype"
+        );
+        let rows = vep.to_tsv_rows();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0],
+            "18:g.31592974G>A\tTTR\tENST00000237014\tmissense_variant\tMODERATE\tENST00000237014.8:c.148G>A\tENSP00000237014.4:p.Val50Met\t1\tprotein_coding"
+        );
+        assert!(rows[1].contains("\tENST00000541025\t"));
+        assert!(rows[1].ends_with("\t0\tprotein_coding"));
+
+        let json =
+            TTR_V30M_VEP_JSON.replacen(r#""canonical":1,"#, r#""canonical":1,"intron":5,"#, 1);
+        let unparseable = serde_json::from_str::<Vec<VEPAnalysis>>(&json)
+            .unwrap()
+            .remove(0);
+        assert_eq!(unparseable.to_tsv_rows()[0], rows[0]);
+
+        let intergenic = VEPAnalysis {
+            transcript_consequences: Vec::new(),
+            ..vep
+        };
+        assert_eq!(
+            intergenic.to_tsv_rows(),
+            ["18:g.31592974G>A\t\t\t\t\t\t\t\t"]
+        );
+    }
+
+    #[test]
+    fn test_codons() {
+        let tc = ttr_v30m().transcript_consequences[0]
+            .as_parseable()
+            .unwrap()
+            .clone();
+        let mut pc = tc.protein_consequences.unwrap();
+        assert_eq!(pc.codons, "Gtg/Atg");
+        assert_eq!(pc.changed_base_positions(), Some(vec![0]));
+
+        pc.codons = "gTg/gAg".to_owned();
+        assert_eq!(pc.reference_codon().as_deref(), Some("GTG"));
+        assert_eq!(pc.alternate_codon().as_deref(), Some("GAG"));
+        assert_eq!(pc.changed_base_positions(), Some(vec![1]));
+
+        pc.codons = "aaGTCcct/aaAGCcct".to_owned();
+        assert_eq!(pc.changed_base_positions(), Some(vec![2, 3]));
+        pc.codons = "aaG/aa".to_owned();
+        assert_eq!(pc.changed_base_positions(), None);
+        pc.codons = String::new();
+        assert_eq!(pc.reference_codon(), None);
+    }
+
+    #[test]
+    fn test_hgvs_protein() {
+        let tc = ttr_v30m().transcript_consequences[0]
+            .as_parseable()
+            .unwrap()
+            .clone();
+        let hgvs = tc.protein_consequences.unwrap().hgvs_protein().unwrap();
+        assert_eq!(hgvs.protein_id.as_deref(), Some("ENSP00000237014.4"));
+        assert_eq!(hgvs.reference.as_deref(), Some("Val"));
+        assert_eq!(hgvs.position, Some(50));
+        assert_eq!(hgvs.edit, ProteinEdit::Substitution("Met".to_owned()));
+
+        let parse = |s: &str| s.parse::<HgvsProtein>().unwrap().edit;
+        assert_eq!(parse("ENSP1:p.Leu12%3D"), ProteinEdit::Synonymous);
+        assert_eq!(parse("p.(Leu12=)"), ProteinEdit::Synonymous);
+        let unchanged = "ENSP1:p.(=)".parse::<HgvsProtein>().unwrap();
+        assert_eq!(
+            (unchanged.position, unchanged.edit),
+            (None, ProteinEdit::Synonymous)
+        );
+        assert_eq!(parse("p.Arg50Ter"), ProteinEdit::Nonsense);
+        assert_eq!(parse("p.Arg50*"), ProteinEdit::Nonsense);
+        assert_eq!(
+            parse("p.Lys2AsnfsTer3"),
+            ProteinEdit::Frameshift {
+                alternate: Some("Asn".to_owned()),
+                stop: Some(3)
+            }
+        );
+        assert_eq!(
+            parse("p.Lys2fs"),
+            ProteinEdit::Frameshift {
+                alternate: None,
+                stop: None
+            }
+        );
+        assert_eq!(
+            parse("p.Lys2AsnfsTer?"),
+            ProteinEdit::Frameshift {
+                alternate: Some("Asn".to_owned()),
+                stop: None
+            }
+        );
+        for unrecognized in [
+            "",
+            "ENSP1:c.148G>A",
+            "p.Val50",
+            "p.Lys2_Pro3del",
+            "p.Ter148GlnextTer?",
+        ] {
+            assert!(
+                unrecognized.parse::<HgvsProtein>().is_err(),
+                "{unrecognized}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_variant_class() {
+        let vep = ttr_v30m();
+        assert_eq!(vep.variant_class, Some(VariantClass::SNV));
+        assert_eq!(
+            vep.variant_class.and_then(|class| class.variant_type()),
+            Some(vep.allele.variant_type())
+        );
+        let classes: Vec<VariantClass> =
+            serde_json::from_str(r#"["deletion","substitution","copy_number_variation"]"#).unwrap();
+        assert_eq!(
+            classes,
+            [
+                VariantClass::deletion,
+                VariantClass::substitution,
+                VariantClass::Unknown
+            ]
+        );
+        assert_eq!(classes[1].variant_type(), Some(VariantType::MNV));
+        assert_eq!(classes[2].variant_type(), None);
+        assert!(
+            <VEPAnalysis as crate::EnsemblPostEndpoint>::payload_template()
+                .contains(r#""variant_class" : 1"#)
+        );
+    }
+
+    #[test]
+    fn test_allele_biallelic() {
+        let allele: Allele = "G/A".parse().unwrap();
+        assert_eq!(allele.normal, "G");
+        assert_eq!(allele.variants, vec!["A"]);
+        assert_eq!(String::from(allele), "G/A");
+    }
+
+    #[test]
+    fn test_allele_triallelic() {
+        let allele: Allele = "A/G/T".parse().unwrap();
+        assert_eq!(allele.normal, "A");
+        assert_eq!(allele.variants, vec!["G", "T"]);
+        assert_eq!(String::from(allele), "A/G/T");
+    }
+
+    #[test]
+    fn test_allele_deletion() {
+        let allele: Allele = "AG/-".parse().unwrap();
+        assert_eq!(allele.normal, "AG");
+        assert_eq!(allele.variants, vec![""]);
+        assert_eq!(String::from(allele.clone()), "AG/-");
+        let json = serde_json::to_string(&allele).unwrap();
+        assert_eq!(json, r#""AG/-""#);
+        assert_eq!(serde_json::from_str::<Allele>(&json).unwrap(), allele);
+        assert!("AG".parse::<Allele>().is_err());
+    }
+
+    #[test]
+    fn test_variant_type() {
+        let variant_type = |s: &str| s.parse::<Allele>().unwrap().variant_type();
+        assert_eq!(variant_type("A/G"), VariantType::SNV);
+        assert_eq!(variant_type("-/GG"), VariantType::Insertion);
+        assert_eq!(variant_type("AAA/-"), VariantType::Deletion);
+        assert_eq!(variant_type("AT/GC"), VariantType::MNV);
+        assert_eq!(variant_type("AT/G"), VariantType::Indel);
+    }
+
+    #[test]
+    fn test_vep_unparseable() {
+        let json = TTR_V30M_VEP_JSON.replace(r#""strand":1,"assembly_name""#, r#""assembly_name""#);
+        let results: Vec<VEPResult> = serde_json::from_str(&json).unwrap();
+        let VEPResult::Unparseable(unparseable) = &results[0] else {
+            panic!("{:?}", results[0]);
+        };
+        assert_eq!(results[0].input(), "18:g.31592974G>A");
+        let err = unparseable.try_into_analysis().unwrap_err();
+        assert!(err.to_string().contains("strand"));
+
+        let results: Vec<VEPResult> = serde_json::from_str(TTR_V30M_VEP_JSON).unwrap();
+        assert!(matches!(results[0], VEPResult::Success(_)));
+        let mut raw: Vec<BTreeMap<String, serde_json::Value>> =
+            serde_json::from_str(TTR_V30M_VEP_JSON).unwrap();
+        let unparseable = VEPUnparseable::from(raw.remove(0));
+        assert_eq!(unparseable.try_into_analysis().unwrap(), ttr_v30m());
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let vep = ttr_v30m();
+        let json = serde_json::to_string(&vep).unwrap();
+        assert_eq!(serde_json::from_str::<VEPAnalysis>(&json).unwrap(), vep);
+        let unparseable_json =
+            TTR_V30M_VEP_JSON.replace(r#""strand":1,"assembly_name""#, r#""assembly_name""#);
+        let mut results: Vec<VEPResult> = serde_json::from_str(TTR_V30M_VEP_JSON).unwrap();
+        results.extend(serde_json::from_str::<Vec<VEPResult>>(&unparseable_json).unwrap());
+        results.push(VEPResult::EnsemblError(crate::EnsemblError {
+            input: "18:g.1A>G".to_owned(),
+            error: "Reference allele mismatch".to_owned(),
+            status_code: 400,
+            retries: 0,
+        }));
+        let json = serde_json::to_string(&results).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<VEPResult>>(&json).unwrap(),
+            results
+        );
+        let frequencies = vep.frequencies();
+        let json = serde_json::to_string(&frequencies).unwrap();
+        assert_eq!(
+            serde_json::from_str::<AlleleFrequencies>(&json).unwrap(),
+            frequencies
+        );
+        let hgvs: HgvsProtein = "ENSP00000237014.4:p.Val50Met".parse().unwrap();
+        let json = serde_json::to_string(&hgvs).unwrap();
+        assert_eq!(serde_json::from_str::<HgvsProtein>(&json).unwrap(), hgvs);
+    }
+}